@@ -1,7 +1,5 @@
 use super::*;
 
-use permutohedron::Heap;
-
 fn parse<'a>(input: &str) -> Vec<Vec<u32>> {
     let no_digit = satisfy(|c: char| !c.is_digit(10));
     let line = no_digit
@@ -42,29 +40,11 @@ fn reverse_triangle(n: usize) -> Option<usize> {
 }
 
 fn part1(grid: &[Vec<u32>]) -> u32 {
-    let mut vec = (0..grid.len()).collect::<Vec<_>>();
-    let mut heap = Heap::new(&mut vec);
-    let mut min = u32::max_value();
-
-    while let Some(permutation) = heap.next_permutation() {
-        let sum: u32 = permutation.windows(2).map(|s| grid[s[0]][s[1]]).sum();
-        min = min.min(sum);
-    }
-
-    min
+    shortest_route(grid.len(), Route::Open, |i, j| grid[i][j]).1
 }
 
 fn part2(grid: &[Vec<u32>]) -> u32 {
-    let mut vec = (0..grid.len()).collect::<Vec<_>>();
-    let mut heap = Heap::new(&mut vec);
-    let mut max = u32::min_value();
-
-    while let Some(permutation) = heap.next_permutation() {
-        let sum: u32 = permutation.windows(2).map(|s| grid[s[0]][s[1]]).sum();
-        max = max.max(sum);
-    }
-
-    max
+    longest_route(grid.len(), Route::Open, |i, j| grid[i][j]).1
 }
 
 pub fn solve(input: &str) -> (u32, u32) {
@@ -1,32 +1,13 @@
-use permutohedron::Heap;
+use crate::utils::{longest_route, Route};
 
 fn part1(changes: [[i32; 8]; 8]) -> i32 {
-    let mut order = [1, 2, 3, 4, 5, 6, 7];
-    let mut heap = Heap::new(&mut order);
-    let mut max = i32::min_value();
-    while let Some(p) = heap.next_permutation() {
-        let pair = |i: usize, j: usize| changes[i][j] + changes[j][i];
-        let change: i32 =
-            pair(0, p[0]) + pair(0, p[6]) + (0..6).map(|i| pair(p[i], p[i + 1])).sum::<i32>();
-        if change > max {
-            max = change;
-        }
-    }
-    max
+    let pair = |i: usize, j: usize| changes[i][j] + changes[j][i];
+    longest_route(8, Route::Closed, pair).1
 }
 
 fn part2(changes: [[i32; 8]; 8]) -> i32 {
-    let mut order = [0, 1, 2, 3, 4, 5, 6, 7];
-    let mut heap = Heap::new(&mut order);
-    let mut max = i32::min_value();
-    while let Some(p) = heap.next_permutation() {
-        let pair = |i: usize, j: usize| changes[i][j] + changes[j][i];
-        let change: i32 = (0..7).map(|i| pair(p[i], p[i + 1])).sum::<i32>();
-        if change > max {
-            max = change;
-        }
-    }
-    max
+    let pair = |i: usize, j: usize| changes[i][j] + changes[j][i];
+    longest_route(8, Route::Open, pair).1
 }
 
 pub fn solve(input: &str) -> (i32, i32) {
@@ -1,57 +1,47 @@
-use super::{Grid, Pos};
+use super::{birth_survival, Automaton, Boundary, DenseGrid, Moore, Pos};
+
+const SIZE: i32 = 100;
+const CORNERS: [Pos; 4] = [
+    Pos { x: 0, y: 0 },
+    Pos { x: 0, y: SIZE - 1 },
+    Pos { x: SIZE - 1, y: 0 },
+    Pos {
+        x: SIZE - 1,
+        y: SIZE - 1,
+    },
+];
+
+fn lights(input: &str) -> Automaton<Pos, Moore, impl Fn(bool, usize) -> bool> {
+    let (grid, _) = DenseGrid::parse(input, |_| false, |b| b == b'#');
+    let on = grid.iter().filter(|&(_, &on)| on).map(|(pos, _)| pos);
+    let boundary = Boundary::Finite {
+        min: Pos::origin(),
+        max: Pos {
+            x: SIZE - 1,
+            y: SIZE - 1,
+        },
+    };
+    Automaton::new(on, Moore(boundary), birth_survival(&[3], &[2, 3]))
+}
 
 pub fn solve(input: &str) -> (usize, usize) {
-    let mut grid = Grid::new(false);
-    for (line, y) in input.lines().zip(0..) {
-        for (b, x) in line.bytes().zip(0..) {
-            let pos = Pos { x, y };
-            if b == b'#' {
-                grid[pos] = true;
-            }
-        }
-    }
-
-    (part1(grid.clone()), part2(grid))
+    (part1(input), part2(input))
 }
 
-fn part1(mut grid: Grid<bool>) -> usize {
-    for _ in 0..100 {
-        let mut new = Grid::new(false);
-        for x in 0..100 {
-            for y in 0..100 {
-                let pos = Pos { x, y };
-                let count = pos.diag_neighbors().filter(|&n| grid[n]).count();
-                if count == 3 || (count == 2 && grid[pos]) {
-                    new[pos] = true;
-                }
-            }
-        }
-        grid = new;
-    }
-
-    grid.iter().filter(|&(_, &x)| x).count()
+fn part1(input: &str) -> usize {
+    let mut lights = lights(input);
+    lights.run(100);
+    lights.len()
 }
 
-fn part2(mut grid: Grid<bool>) -> usize {
+fn part2(input: &str) -> usize {
+    let mut lights = lights(input);
+    lights.alive.extend(&CORNERS);
     for _ in 0..100 {
-        let mut new = Grid::new(false);
-        for x in 0..100 {
-            for y in 0..100 {
-                let pos = Pos { x, y };
-                let count = pos.diag_neighbors().filter(|&n| grid[n]).count();
-                if count == 3 || (count == 2 && grid[pos]) {
-                    new[pos] = true;
-                }
-            }
-        }
-        new[Pos { x: 0, y: 0 }] = true;
-        new[Pos { x: 0, y: 99 }] = true;
-        new[Pos { x: 99, y: 0 }] = true;
-        new[Pos { x: 99, y: 99 }] = true;
-        grid = new;
+        lights.step();
+        lights.alive.extend(&CORNERS);
     }
-
-    grid.iter().filter(|&(_, &x)| x).count()
+    lights.len()
 }
 
 #[cfg(test)]
@@ -6,24 +6,6 @@ enum Side {
     Right,
 }
 
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn turn(self, side: Side) -> Direction {
-        match (self, side) {
-            (Direction::Up, Side::Left) | (Direction::Down, Side::Right) => Direction::Left,
-            (Direction::Right, Side::Left) | (Direction::Left, Side::Right) => Direction::Up,
-            (Direction::Down, Side::Left) | (Direction::Up, Side::Right) => Direction::Right,
-            (Direction::Left, Side::Left) | (Direction::Right, Side::Right) => Direction::Down,
-        }
-    }
-}
-
 struct Instruction {
     side: Side,
     amount: i32,
@@ -41,42 +23,34 @@ fn parser<'a>() -> impl Parser<&'a str, Output = Vec<Instruction>> {
     instruction.collect_sep_by(string(", "))
 }
 
-fn part1(instructions: &[Instruction]) -> i32 {
-    let mut h = 0;
-    let mut v = 0;
-    let mut direction = Direction::Up;
-
-    for instruction in instructions {
-        direction = direction.turn(instruction.side);
-        match direction {
-            Direction::Up => v -= instruction.amount,
-            Direction::Down => v += instruction.amount,
-            Direction::Left => h -= instruction.amount,
-            Direction::Right => h += instruction.amount,
+/// The direction after every turn, along with the distance to walk in it.
+fn directions(instructions: &[Instruction]) -> impl Iterator<Item = (Dir, i32)> + '_ {
+    instructions.iter().scan(Dir::North, |dir, instruction| {
+        match instruction.side {
+            Side::Left => dir.turn_left(),
+            Side::Right => dir.turn_right(),
         }
-    }
+        Some((*dir, instruction.amount))
+    })
+}
 
-    h.abs() + v.abs()
+fn part1(instructions: &[Instruction]) -> i32 {
+    let end = directions(instructions).fold(Pos::origin(), |pos, (dir, amount)| {
+        pos + Pos::from(dir) * amount
+    });
+    end.manhattan_distance(Pos::origin())
 }
 
 fn part2(instructions: &[Instruction]) -> i32 {
     let mut set = HashSet::new();
-    let mut location = (0_i32, 0_i32);
-    let mut direction = Direction::Up;
-
-    for instruction in instructions {
-        direction = direction.turn(instruction.side);
+    let mut location = Pos::origin();
 
-        for _ in 0..instruction.amount {
-            match direction {
-                Direction::Up => location.1 -= 1,
-                Direction::Down => location.1 += 1,
-                Direction::Left => location.0 -= 1,
-                Direction::Right => location.0 += 1,
-            }
+    for (dir, amount) in directions(instructions) {
+        for _ in 0..amount {
+            location.move_to(dir);
 
             if !set.insert(location) {
-                return location.0.abs() + location.1.abs();
+                return location.manhattan_distance(Pos::origin());
             }
         }
     }
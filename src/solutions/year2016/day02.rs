@@ -1,127 +1,50 @@
 use super::*;
 
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Direction {
-    fn offset1(&self, digit: char) -> char {
-        match (self, digit) {
-            (Direction::Right, '1') => '2',
-            (Direction::Down, '1') => '4',
-            (Direction::Right, '2') => '3',
-            (Direction::Down, '2') => '5',
-            (Direction::Left, '2') => '1',
-            (Direction::Down, '3') => '6',
-            (Direction::Left, '3') => '2',
-            (Direction::Up, '4') => '1',
-            (Direction::Right, '4') => '5',
-            (Direction::Down, '4') => '7',
-            (Direction::Up, '5') => '2',
-            (Direction::Right, '5') => '6',
-            (Direction::Down, '5') => '8',
-            (Direction::Left, '5') => '4',
-            (Direction::Up, '6') => '3',
-            (Direction::Down, '6') => '9',
-            (Direction::Left, '6') => '5',
-            (Direction::Up, '7') => '4',
-            (Direction::Right, '7') => '8',
-            (Direction::Up, '8') => '5',
-            (Direction::Right, '8') => '9',
-            (Direction::Left, '8') => '7',
-            (Direction::Up, '9') => '6',
-            (Direction::Left, '9') => '8',
-            (_, digit) => digit,
-        }
-    }
-
-    fn offset2(&self, digit: char) -> char {
-        match (self, digit) {
-            (Direction::Down, '1') => '3',
-
-            (Direction::Right, '2') => '3',
-            (Direction::Down, '2') => '6',
-
-            (Direction::Up, '3') => '1',
-            (Direction::Right, '3') => '4',
-            (Direction::Down, '3') => '7',
-            (Direction::Left, '3') => '2',
-
-            (Direction::Down, '4') => '8',
-            (Direction::Left, '4') => '3',
-
-            (Direction::Right, '5') => '6',
-
-            (Direction::Up, '6') => '2',
-            (Direction::Right, '6') => '7',
-            (Direction::Down, '6') => 'A',
-            (Direction::Left, '6') => '5',
-
-            (Direction::Up, '7') => '3',
-            (Direction::Right, '7') => '8',
-            (Direction::Down, '7') => 'B',
-            (Direction::Left, '7') => '6',
-
-            (Direction::Up, '8') => '4',
-            (Direction::Right, '8') => '9',
-            (Direction::Down, '8') => 'C',
-            (Direction::Left, '8') => '7',
-
-            (Direction::Left, '9') => '8',
-
-            (Direction::Up, 'A') => '6',
-            (Direction::Right, 'A') => 'B',
-
-            (Direction::Up, 'B') => '7',
-            (Direction::Right, 'B') => 'C',
-            (Direction::Down, 'B') => 'D',
-            (Direction::Left, 'B') => 'A',
-
-            (Direction::Up, 'C') => '8',
-            (Direction::Left, 'C') => 'B',
-
-            (Direction::Up, 'D') => 'B',
-
-            (_, digit) => digit,
-        }
-    }
-}
-
-fn parser<'a>() -> impl Parser<&'a str, Output = Vec<Vec<Direction>>> {
-    let direction = choice((
-        token('U').map(|_| Direction::Up),
-        token('R').map(|_| Direction::Right),
-        token('D').map(|_| Direction::Down),
-        token('L').map(|_| Direction::Left),
-    ));
+const KEYPAD1: &str = "\
+123
+456
+789";
+
+const KEYPAD2: &str = "\
+..1..
+.234.
+56789
+.ABC.
+..D..";
+
+fn parser<'a>() -> impl Parser<&'a str, Output = Vec<Vec<Dir>>> {
+    let direction = satisfy(|c: char| c.is_ascii_uppercase()).and_then(Dir::from_char);
     let digit = direction.collect_many1();
     let digits = digit.collect_sep_by(token('\n'));
     digits
 }
 
-fn part1(digits: &[Vec<Direction>]) -> String {
-    let mut current = '5';
+/// Follows the instructions on a keypad, starting at `5` and ignoring moves that don't lead
+/// to a key.
+fn code(keypad: &str, digits: &[Vec<Dir>]) -> String {
+    let (keypad, _) = DenseGrid::parse(keypad, |_| false, |b| b);
+    let is_key = |pos| keypad.get(pos).map_or(false, |&b| b != b'.');
+    let mut current = keypad.position(|&b| b == b'5').unwrap();
     digits
         .iter()
         .map(|digit| {
-            current = digit.iter().fold(current, |c, d| d.offset1(c));
-            current
+            for &dir in digit {
+                let next = current.moving_to(dir);
+                if is_key(next) {
+                    current = next;
+                }
+            }
+            keypad[current] as char
         })
         .collect()
 }
 
-fn part2(digits: &[Vec<Direction>]) -> String {
-    let mut current = '5';
-    digits
-        .iter()
-        .map(|digit| {
-            current = digit.iter().fold(current, |c, d| d.offset2(c));
-            current
-        })
-        .collect()
+fn part1(digits: &[Vec<Dir>]) -> String {
+    code(KEYPAD1, digits)
+}
+
+fn part2(digits: &[Vec<Dir>]) -> String {
+    code(KEYPAD2, digits)
 }
 
 pub fn solve(input: &str) -> (String, String) {
@@ -1,25 +1,10 @@
 use super::*;
 
-#[derive(Copy, Clone)]
-enum State {
-    On,
-    Off,
-}
-
-impl Debug for State {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::On => write!(f, "#"),
-            Self::Off => write!(f, "."),
-        }
-    }
-}
-
 #[derive(Debug)]
 enum Operation {
     Rect { width: i32, height: i32 },
-    RotateRow { y: i32, by: usize },
-    RotateColumn { x: i32, by: usize },
+    RotateRow { y: usize, by: usize },
+    RotateColumn { x: usize, by: usize },
 }
 
 fn rect<'a>() -> impl Parser<&'a str, Output = Operation> {
@@ -30,7 +15,7 @@ fn rect<'a>() -> impl Parser<&'a str, Output = Operation> {
 fn rotate_row<'a>() -> impl Parser<&'a str, Output = Operation> {
     chain((
         string("rotate row y="),
-        parser::i32(),
+        parser::usize(),
         string(" by "),
         parser::usize(),
     ))
@@ -40,7 +25,7 @@ fn rotate_row<'a>() -> impl Parser<&'a str, Output = Operation> {
 fn rotate_column<'a>() -> impl Parser<&'a str, Output = Operation> {
     chain((
         string("rotate column x="),
-        parser::i32(),
+        parser::usize(),
         string(" by "),
         parser::usize(),
     ))
@@ -51,50 +36,38 @@ fn parser<'a>() -> impl Parser<&'a str, Output = Vec<Operation>> {
     choice((rect().attempt(), rotate_row().attempt(), rotate_column())).collect_sep_by(newline())
 }
 
-pub fn solve(input: &str) {
-    const WIDTH: i32 = 50;
-    const HEIGHT: i32 = 6;
-
-    let mut grid = Grid::new(State::Off);
+const WIDTH: usize = 50;
+const HEIGHT: usize = 6;
 
-    for op in parser().parse_to_end(input).unwrap() {
-        match op {
-            Operation::Rect { width, height } => {
-                for x in 0..width {
-                    for y in 0..height {
-                        grid[Pos { x, y }] = State::On;
-                    }
+fn apply(grid: &mut DenseGrid<bool>, op: Operation) {
+    match op {
+        Operation::Rect { width, height } => {
+            for x in 0..width {
+                for y in 0..height {
+                    grid[Pos { x, y }] = true;
                 }
             }
-            Operation::RotateRow { y, by } => {
-                let mut vec: Vec<_> = (0..WIDTH).map(|x| grid[Pos { x, y }]).collect();
-                vec.rotate_right(by);
-                (0..WIDTH)
-                    .zip(vec)
-                    .for_each(|(x, state)| grid[Pos { x, y }] = state);
-            }
-            Operation::RotateColumn { x, by } => {
-                let mut vec: Vec<_> = (0..HEIGHT).map(|y| grid[Pos { x, y }]).collect();
-                vec.rotate_right(by);
-                (0..HEIGHT)
-                    .zip(vec)
-                    .for_each(|(y, state)| grid[Pos { x, y }] = state);
-            }
         }
+        Operation::RotateRow { y, by } => grid.rotate_row(y, by),
+        Operation::RotateColumn { x, by } => grid.rotate_column(x, by),
+    }
+}
+
+pub fn solve(input: &str) -> (usize, String) {
+    let mut grid = DenseGrid::new(WIDTH, HEIGHT, false);
+
+    for op in parser().parse_to_end(input).unwrap() {
+        apply(&mut grid, op);
     }
 
-    println!(
-        "{}",
-        grid.iter()
-            .filter(|&(_, &state)| matches!(state, State::On))
-            .count()
-    );
-    println!("{:?}", grid);
+    let part1 = grid.iter().filter(|&(_, &on)| on).count();
+    let part2 = ocr(&grid, |&on| on).unwrap();
+    (part1, part2)
 }
 
 #[async_std::test]
 async fn test() -> Result<(), InputError> {
     let input = get_input(2016, 8).await?;
-    solve(&input);
+    assert_eq!(solve(&input), (106, "CFLELOYFCS".into()));
     Ok(())
 }
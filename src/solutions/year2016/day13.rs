@@ -1,64 +1,25 @@
 use super::*;
 
-fn part1(n: i32) -> i32 {
-    let is_wall = |Pos { x, y }| {
+const START: Pos = Pos { x: 1, y: 1 };
+
+fn maze(n: i32) -> Flood<impl FnMut(Pos) -> bool> {
+    let is_open = move |Pos { x, y }| {
         let sum = (x * x + 3 * x + 2 * x * y + y + y * y) + n;
-        sum.count_ones() % 2 == 1
+        x >= 0 && y >= 0 && sum.count_ones() % 2 == 0
     };
+    flood(iter::once(START), is_open)
+}
 
-    let start = Pos { x: 1, y: 1 };
+fn part1(n: i32) -> usize {
     let goal = Pos { x: 31, y: 39 };
-
-    let mut seen = HashSet::new();
-    let mut current = vec![start];
-    let mut next = vec![];
-
-    for distance in 0.. {
-        for p in current.drain(..) {
-            if p == goal {
-                return distance;
-            }
-            for n in p.non_neg_neighbors().filter(|&n| !is_wall(n)) {
-                if seen.insert(n) {
-                    next.push(n);
-                }
-            }
-        }
-
-        mem::swap(&mut current, &mut next);
-    }
-
-    unreachable!()
+    maze(n).find(|&(pos, _)| pos == goal).unwrap().1
 }
 
 fn part2(n: i32) -> usize {
-    let is_wall = |Pos { x, y }| {
-        let sum = (x * x + 3 * x + 2 * x * y + y + y * y) + n;
-        sum.count_ones() % 2 == 1
-    };
-
-    let start = Pos { x: 1, y: 1 };
-
-    let mut seen = HashSet::new();
-    let mut current = vec![start];
-    let mut next = vec![];
-
-    for _ in 0..50 {
-        for p in current.drain(..) {
-            for n in p.non_neg_neighbors().filter(|&n| !is_wall(n)) {
-                if seen.insert(n) {
-                    next.push(n);
-                }
-            }
-        }
-
-        mem::swap(&mut current, &mut next);
-    }
-
-    seen.len()
+    maze(n).take_while(|&(_, distance)| distance <= 50).count()
 }
 
-pub fn solve(input: &str) -> (i32, usize) {
+pub fn solve(input: &str) -> (usize, usize) {
     let input = input.parse().unwrap();
     (part1(input), part2(input))
 }
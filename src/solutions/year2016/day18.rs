@@ -1,29 +1,35 @@
+use super::*;
+use mask::*;
+
+/// A tile is a trap if exactly one of the tiles to its left and right in the previous row is.
+fn trap_rows(len: u32) -> MaskAutomaton<u128> {
+    let sides = [Pos { x: -1, y: 0 }, Pos { x: 1, y: 0 }];
+    MaskAutomaton::grid(len, 1, &sides, birth_survival(&[1], &[1]))
+}
+
 pub fn solve(input: &str) -> (u32, u32) {
-    let mut traps = 0_u128;
+    let mut traps = Mask::empty();
     for (i, byte) in input.bytes().enumerate() {
         if byte == b'^' {
-            traps |= 1 << i;
+            traps.insert(i as u32);
         }
     }
     let len = input.len() as u32;
     (safe_count(40, traps, len), safe_count(400_000, traps, len))
 }
 
-fn safe_count(rows: u32, mut traps: u128, len: u32) -> u32 {
-    let mask = (1 << len) - 1;
+fn safe_count(rows: u32, mut traps: Mask<u128>, len: u32) -> u32 {
+    let automaton = trap_rows(len);
     let mut trap_count = 0;
 
     for _ in 0..rows {
-        trap_count += traps.count_ones();
-        traps = mask & ((traps << 1) ^ (traps >> 1));
+        trap_count += traps.len();
+        traps = automaton.step(traps);
     }
 
     rows * len - trap_count
 }
 
-#[cfg(test)]
-use super::*;
-
 #[async_std::test]
 async fn test() -> Result<(), InputError> {
     let input = get_input(2016, 18).await?;
@@ -1,7 +1,7 @@
 use super::*;
 use assembunny::*;
 
-pub fn solve(input: &str) {
+pub fn solve(input: &str) -> (i32, i32) {
     let instructions = parser().parse_to_end(input).unwrap();
 
     let mut vm = VM {
@@ -9,22 +9,23 @@ pub fn solve(input: &str) {
         ip: 0,
         regs: Regs([7, 0, 0, 0]),
     };
-    vm.run();
-    println!("part 1: {}", vm.regs.0[0]);
+    vm.run_optimized();
+    let part1 = vm.regs.0[0];
 
     let mut vm = VM {
         instructions,
         ip: 0,
         regs: Regs([12, 0, 0, 0]),
     };
-    vm.run();
-    println!("part 2: {}", vm.regs.0[0]);
+    vm.run_optimized();
+    let part2 = vm.regs.0[0];
+
+    (part1, part2)
 }
 
 #[async_std::test]
 async fn test() -> Result<(), InputError> {
     let input = get_input(2016, 23).await?;
-    // assert_eq!(solve(&input), (317_993, 9_227_647));
-    solve(&input);
+    assert_eq!(solve(&input), (14_445, 479_011_005));
     Ok(())
 }
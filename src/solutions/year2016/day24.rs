@@ -1,64 +1,17 @@
 use super::*;
 
-pub fn solve(input: &str) -> (u32, u32) {
-    let mut path = HashSet::new();
-    let mut numbers = [(0, 0); 8];
-    let mut map = HashMap::new();
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, &c) in line.as_bytes().iter().enumerate() {
-            if c == b'#' {
-                continue;
-            }
-
-            path.insert((x, y));
-
-            if c != b'.' {
-                let n = (c - b'0') as usize;
-                numbers[n] = (x, y);
-                map.insert((x, y), n);
-            }
-        }
-    }
-
-    let mut seen = HashSet::new();
-    let mut nodes = vec![(numbers[0], 0)];
-
-    let mut part1 = None;
-
-    for round in 0.. {
-        let mut new = Vec::new();
-
-        for ((x, y), mut mask) in nodes {
-            if let Some(&n) = map.get(&(x, y)) {
-                mask |= 1 << n;
-
-                if mask == 0b1111_1111 {
-                    if part1.is_none() {
-                        part1 = Some(round);
-                    }
-
-                    if n == 0 {
-                        return (part1.unwrap(), round);
-                    }
-                }
-            }
-
-            if !seen.insert(((x, y), mask)) {
-                continue;
-            }
-
-            for (x, y) in iter!([(x + 1, y), (x - 1, y), (x, y - 1), (x, y + 1)]) {
-                if path.contains(&(x, y)) {
-                    new.push(((x, y), mask));
-                }
-            }
-        }
-
-        nodes = new;
-    }
-
-    unreachable!()
+pub fn solve(input: &str) -> (usize, usize) {
+    let (grid, markers) = DenseGrid::parse(input, |c| c.is_ascii_digit(), |c| c);
+    let mut digits: Vec<(u8, Pos)> = markers.iter().collect();
+    digits.sort_by_key(|&(digit, _)| digit);
+
+    let points: Vec<Pos> = digits.iter().map(|&(_, pos)| pos).collect();
+    let graph = MazeGraph::<u32>::new(&grid, &points, |&c| c != b'#', |_| None);
+    let distance = |from, to| graph.distance(from, to).unwrap();
+
+    let part1 = shortest_route(points.len(), Route::OpenFrom(0), distance).1;
+    let part2 = shortest_route(points.len(), Route::Closed, distance).1;
+    (part1, part2)
 }
 
 #[async_std::test]
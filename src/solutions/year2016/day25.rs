@@ -0,0 +1,38 @@
+use super::*;
+use assembunny::*;
+
+pub fn solve(input: &str) -> i32 {
+    let instructions = parser().parse_to_end(input).unwrap();
+
+    (0..)
+        .find(|&a| {
+            VM {
+                instructions: instructions.clone(),
+                ip: 0,
+                regs: Regs([a, 0, 0, 0]),
+            }
+            .is_clock_signal()
+        })
+        .unwrap()
+}
+
+#[async_std::test]
+async fn test() -> Result<(), InputError> {
+    let input = get_input(2016, 25).await?;
+    let answer = solve(&input);
+
+    // checks the answer of the optimized machine against the plain one
+    let instructions = parser().parse_to_end(&input).unwrap();
+    let is_clock_signal = |a| {
+        let mut vm = VM {
+            instructions: instructions.clone(),
+            ip: 0,
+            regs: Regs([a, 0, 0, 0]),
+        };
+        let expected = [0, 1].iter().copied().cycle().take(32);
+        vm.outputs().take(32).eq(expected)
+    };
+    assert!(is_clock_signal(answer));
+    assert!((0..answer).all(|a| !is_clock_signal(a)));
+    Ok(())
+}
@@ -20,5 +20,4 @@ pub mod day21;
 pub mod day22;
 pub mod day23;
 pub mod day24;
-
-mod assembunny;
+pub mod day25;
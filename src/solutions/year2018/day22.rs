@@ -1,5 +1,5 @@
 use super::*;
-use search_algs::*;
+use fxhash::FxBuildHasher;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 enum Type {
@@ -57,8 +57,8 @@ impl Type {
 fn erosion(
     depth: i32,
     target: Pos,
-) -> Cache<Pos, i32, impl Fn(&Pos, &mut dyn FnMut(Pos) -> i32) -> i32> {
-    Cache::new(move |&pos, f| {
+) -> Cache<Pos, i32, impl Fn(&Pos, &mut dyn FnMut(Pos) -> i32) -> i32, FxBuildHasher> {
+    let compute = move |&pos: &Pos, f: &mut dyn FnMut(Pos) -> i32| {
         let from_geo = |geo| (geo + depth) % 20_183;
         let geo = match pos {
             Pos { x: 0, y: 0 } => 0,
@@ -68,7 +68,8 @@ fn erosion(
             _ => f(pos.moving_to(Dir::North)) * f(pos.moving_to(Dir::West)),
         };
         from_geo(geo)
-    })
+    };
+    Cache::with_hasher(compute, FxBuildHasher::default())
 }
 
 fn part1(depth: i32, target: Pos) -> i32 {
@@ -79,37 +80,58 @@ fn part1(depth: i32, target: Pos) -> i32 {
         .sum()
 }
 
+struct Cave<F> {
+    target: Pos,
+    erosion: RefCell<Cache<Pos, i32, F, FxBuildHasher>>,
+}
+
+impl<F> Cave<F>
+where
+    F: Fn(&Pos, &mut dyn FnMut(Pos) -> i32) -> i32,
+{
+    fn type_of(&self, pos: Pos) -> Type {
+        Type::new(self.erosion.borrow_mut().get(pos))
+    }
+}
+
+impl<F> Dijkstra for Cave<F>
+where
+    F: Fn(&Pos, &mut dyn FnMut(Pos) -> i32) -> i32,
+{
+    type Node = (Pos, State);
+    type Cost = i32;
+
+    fn start(&self) -> Self::Node {
+        (Pos::origin(), State::Torch)
+    }
+
+    fn is_goal(&self, &(pos, state): &Self::Node) -> bool {
+        pos == self.target && state == State::Torch
+    }
+
+    fn neighbors(&self, &(pos, state): &Self::Node, mut add: impl FnMut(Self::Node, i32)) {
+        for p in pos.non_neg_neighbors() {
+            if state.works_for(self.type_of(p)) {
+                add((p, state), 1);
+            }
+        }
+        add((pos, state.other_state_for(self.type_of(pos))), 7);
+    }
+
+    fn heuristic(&self, &(pos, _): &Self::Node) -> i32 {
+        pos.manhattan_distance(self.target)
+    }
+}
+
+fn cave(depth: i32, target: Pos) -> Cave<impl Fn(&Pos, &mut dyn FnMut(Pos) -> i32) -> i32> {
+    Cave {
+        target,
+        erosion: RefCell::new(erosion(depth, target)),
+    }
+}
+
 fn part2(depth: i32, target: Pos) -> i32 {
-    let mut erosion = Cache::new(|&pos, f| {
-        let from_geo = |geo| (geo + depth) % 20_183;
-        let geo = match pos {
-            Pos { x: 0, y: 0 } => 0,
-            pos if pos == target => 0,
-            Pos { x, y: 0 } => x * 16_807,
-            Pos { x: 0, y } => y * 48_271,
-            _ => f(pos.moving_to(Dir::North)) * f(pos.moving_to(Dir::West)),
-        };
-        from_geo(geo)
-    });
-    let mut type_of = |pos| Type::new(erosion.get(pos));
-
-    let (_, len) = astar(
-        &(Pos::origin(), State::Torch),
-        |&(pos, state)| {
-            let mut vec: Vec<_> = pos
-                .non_neg_neighbors()
-                .filter(|&p| state.works_for(type_of(p)))
-                .map(|p| ((p, state), 1))
-                .collect();
-            vec.push(((pos, state.other_state_for(type_of(pos))), 7));
-            vec
-        },
-        |&(pos, _)| pos.manhattan_distance(target),
-        |&(pos, state)| pos == target && state == State::Torch,
-    )
-    .unwrap();
-
-    len
+    cave(depth, target).run().unwrap()
 }
 
 fn parser<'a>() -> impl Parser<&'a str, Output = (i32, Pos)> {
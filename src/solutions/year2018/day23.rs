@@ -1,43 +1,18 @@
 use super::*;
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
-struct Point {
-    x: i32,
-    y: i32,
-    z: i32,
-}
-
-impl Point {
-    fn distance_to(&self, position: Point) -> i32 {
-        (self.x - position.x).abs() + (self.y - position.y).abs() + (self.z - position.z).abs()
-    }
-
-    fn distance_to_origin(&self) -> i32 {
-        self.x.abs() + self.y.abs() + self.z.abs()
-    }
-}
-
-impl Ord for Point {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.distance_to_origin().cmp(&(other.distance_to_origin()))
-    }
-}
-
-impl PartialOrd for Point {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+fn distance_to_origin(point: Pos3) -> i32 {
+    point.manhattan_distance(Pos3::origin())
 }
 
 /// A cube with a side length of a power of 2.
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 struct Cube {
-    origin: Point,
+    origin: Pos3,
     side_length: i32,
 }
 
 impl Cube {
-    fn new(origin: Point, exponent: u32) -> Cube {
+    fn new(origin: Pos3, exponent: u32) -> Cube {
         let side_length = 1 << exponent;
 
         Cube {
@@ -48,34 +23,17 @@ impl Cube {
 
     /// Splits the cube into smaller cubes each with half the side
     /// length of the original cube.
-    fn split(&self) -> [Self; 8] {
+    fn split(self) -> impl Iterator<Item = Self> {
         let side_length = self.side_length / 2;
-        let cube = |x: bool, y: bool, z: bool| -> Self {
-            let extra = |flag: bool| if flag { side_length } else { 0 };
-            Self {
-                origin: Point {
-                    x: self.origin.x + extra(x),
-                    y: self.origin.y + extra(y),
-                    z: self.origin.z + extra(z),
-                },
-                side_length,
-            }
-        };
-        [
-            cube(false, false, false),
-            cube(false, false, true),
-            cube(false, true, false),
-            cube(false, true, true),
-            cube(true, false, false),
-            cube(true, false, true),
-            cube(true, true, false),
-            cube(true, true, true),
-        ]
+        (0..8).map(move |i| Self {
+            origin: self.origin + PosN([i >> 2 & 1, i >> 1 & 1, i & 1]) * side_length,
+            side_length,
+        })
     }
 
     /// Returns the origin if that's the only point inside the cube,
     /// and `None` otherwise.
-    fn only_point(&self) -> Option<Point> {
+    fn only_point(&self) -> Option<Pos3> {
         if self.side_length == 1 {
             Some(self.origin)
         } else {
@@ -86,14 +44,14 @@ impl Cube {
 
 #[derive(Debug)]
 struct NanoBot {
-    position: Point,
+    position: Pos3,
     radius: i32,
 }
 
 impl NanoBot {
     /// Returns `true` if the given point is in range of the bot.
-    fn reaches_point(&self, point: Point) -> bool {
-        self.position.distance_to(point) <= self.radius
+    fn reaches_point(&self, point: Pos3) -> bool {
+        self.position.manhattan_distance(point) <= self.radius
     }
 
     /// Returns `true` if any point of the cube is in range of the bot.
@@ -108,9 +66,9 @@ impl NanoBot {
             }
         };
 
-        distance(self.position.x, cube.origin.x)
-            + distance(self.position.y, cube.origin.y)
-            + distance(self.position.z, cube.origin.z)
+        (0..3)
+            .map(|i| distance(self.position[i], cube.origin[i]))
+            .sum::<i32>()
             <= self.radius
     }
 }
@@ -136,7 +94,7 @@ impl Ord for OrdCube {
             .cmp(&other.bots_in_range)
             // if both cubes have the same number of bots in range,
             // the one closer to the origin is considered "larger"
-            .then(other.cube.origin.cmp(&self.cube.origin))
+            .then(distance_to_origin(other.cube.origin).cmp(&distance_to_origin(self.cube.origin)))
     }
 }
 
@@ -158,16 +116,12 @@ impl OrdCube {
     /// Splits the cube into smaller cubes each with half the side
     /// length of the original cube.
     fn split(&self, bots: &[NanoBot]) -> Vec<Self> {
-        self.cube
-            .split()
-            .iter()
-            .map(|&c| OrdCube::new(c, bots))
-            .collect()
+        self.cube.split().map(|c| OrdCube::new(c, bots)).collect()
     }
 
     /// Returns the origin if that's the only point inside the cube,
     /// and `None` otherwise.
-    fn only_point(&self) -> Option<Point> {
+    fn only_point(&self) -> Option<Pos3> {
         self.cube.only_point()
     }
 }
@@ -175,11 +129,7 @@ impl OrdCube {
 fn parser<'a>() -> impl Parser<&'a str, Output = Vec<NanoBot>> {
     let position = parser::i32()
         .sep_by(token(','), |iter| {
-            Some(Point {
-                x: iter.next()?,
-                y: iter.next()?,
-                z: iter.next()?,
-            })
+            Some(PosN([iter.next()?, iter.next()?, iter.next()?]))
         })
         .between(token('<'), token('>'));
     let bot = chain((string("pos="), position, string(", r="), parser::i32()))
@@ -195,24 +145,9 @@ fn part1(bots: &[NanoBot]) -> u32 {
 }
 
 fn part2(bots: &[NanoBot]) -> i32 {
-    let xs = || bots.iter().map(|b| b.position.x);
-    let ys = || bots.iter().map(|b| b.position.y);
-    let zs = || bots.iter().map(|b| b.position.z);
-
-    let min_x = xs().min().unwrap();
-    let max_x = xs().max().unwrap();
-    let min_y = ys().min().unwrap();
-    let max_y = ys().max().unwrap();
-    let min_z = zs().min().unwrap();
-    let max_z = zs().max().unwrap();
-
-    let size = cmp::max(cmp::max(max_x - min_x, max_y - min_y), max_z - min_z);
+    let (origin, max) = Pos3::bounding_box(bots.iter().map(|b| b.position)).unwrap();
+    let size = origin.chebyshev_distance(max);
     let exponent = 8 * mem::size_of::<i32>() as u32 - size.leading_zeros();
-    let origin = Point {
-        x: min_x,
-        y: min_y,
-        z: min_z,
-    };
 
     let cube = OrdCube::new(Cube::new(origin, exponent), &bots);
     let mut heap = BinaryHeap::new();
@@ -221,7 +156,7 @@ fn part2(bots: &[NanoBot]) -> i32 {
     while let Some(cube) = heap.pop() {
         match cube.only_point() {
             None => cube.split(bots).into_iter().for_each(|c| heap.push(c)),
-            Some(point) => return point.distance_to_origin(),
+            Some(point) => return distance_to_origin(point),
         }
     }
 
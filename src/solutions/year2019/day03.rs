@@ -1,40 +1,7 @@
 use super::*;
 
-#[derive(Copy, Clone)]
-enum Direction {
-    Right,
-    Down,
-    Left,
-    Up,
-}
-
-#[derive(PartialEq, Eq, Hash, Copy, Clone)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    fn move_to(&mut self, dir: Direction) {
-        self.x += match dir {
-            Direction::Right => 1,
-            Direction::Left => -1,
-            _ => 0,
-        };
-        self.y += match dir {
-            Direction::Up => 1,
-            Direction::Down => -1,
-            _ => 0,
-        };
-    }
-
-    fn manhattan_distance(self) -> u32 {
-        self.x.abs() as u32 + self.y.abs() as u32
-    }
-}
-
 struct Segment {
-    dir: Direction,
+    dir: Dir,
     len: u32,
 }
 
@@ -43,11 +10,11 @@ struct Wire {
 }
 
 impl Wire {
-    fn iter(&self) -> impl Iterator<Item = Position> + '_ {
+    fn iter(&self) -> impl Iterator<Item = Pos> + '_ {
         self.segments
             .iter()
             .flat_map(|segment| iter::repeat(segment.dir).take(segment.len as usize))
-            .scan(Position { x: 0, y: 0 }, |pos, dir| {
+            .scan(Pos::origin(), |pos, dir| {
                 pos.move_to(dir);
                 Some(*pos)
             })
@@ -55,12 +22,7 @@ impl Wire {
 }
 
 fn parser<'a>() -> impl Parser<&'a str, Output = [Wire; 2]> {
-    let dir = choice((
-        token('R').map(|_| Direction::Right),
-        token('D').map(|_| Direction::Down),
-        token('L').map(|_| Direction::Left),
-        token('U').map(|_| Direction::Up),
-    ));
+    let dir = satisfy(|c: char| c.is_ascii_uppercase()).and_then(Dir::from_char);
     let segment = dir
         .followed_by(parser::u32())
         .map(|(dir, len)| Segment { dir, len });
@@ -70,12 +32,16 @@ fn parser<'a>() -> impl Parser<&'a str, Output = [Wire; 2]> {
     chain((wire, newline(), wire)).map(|(a, _, b)| [a, b])
 }
 
+fn distance_to_origin(pos: Pos) -> u32 {
+    pos.manhattan_distance(Pos::origin()) as u32
+}
+
 fn part1([wire_a, wire_b]: [&Wire; 2]) -> u32 {
     let breadcrumbs: HashSet<_> = wire_a.iter().collect();
     wire_b
         .iter()
         .filter(|pos| breadcrumbs.contains(pos))
-        .map(Position::manhattan_distance)
+        .map(distance_to_origin)
         .min()
         .unwrap()
 }
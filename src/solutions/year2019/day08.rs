@@ -28,30 +28,21 @@ fn part2(input: &str) -> String {
             }
         }
     }
-    pixels
+    let image: String = pixels
         .chunks(WIDTH)
         .flat_map(|row| row.iter().copied().chain(iter::once('\n')))
-        .collect()
+        .collect();
+    ocr_str(&image).unwrap()
 }
 
 pub fn solve(input: &str) -> (usize, String) {
     (part1(input), part2(input))
 }
 
-#[cfg(test)]
-const FHJUL: &str = "\
-**** *  *   ** *  * *    
-*    *  *    * *  * *    
-***  ****    * *  * *    
-*    *  *    * *  * *    
-*    *  * *  * *  * *    
-*    *  *  **   **  **** 
-";
-
 #[async_std::test]
 async fn test() -> Result<(), InputError> {
     let input = get_input(2019, 8).await?;
     assert_eq!(part1(&input), 2250);
-    assert_eq!(part2(&input), FHJUL);
+    assert_eq!(part2(&input), "FHJUL");
     Ok(())
 }
@@ -1,4 +1,5 @@
 use super::*;
+use arcade::Renderer;
 use intcode::prelude::*;
 
 #[derive(Copy, Clone, PartialEq)]
@@ -18,22 +19,23 @@ impl Debug for Color {
 
 fn part1(memory: Vec<i64>) -> usize {
     let mut bot = Computer::new(memory);
-    let mut paint = Grid::new(Color::Black);
+    let mut paint = Renderer::new(Color::Black);
     let mut pos = Pos::origin();
     let mut dir = Dir::North;
 
     loop {
-        let input = (paint[pos] == Color::White) as i64;
+        let input = (paint.grid[pos] == Color::White) as i64;
         let color = match bot.step_with(input) {
-            Interrupt::Halt => return paint.iter().count(),
+            Interrupt::Halt => return paint.grid.iter().count(),
             Interrupt::WaitingForInput => unreachable!(),
             Interrupt::Output(x) => x,
         };
-        paint[pos] = match color {
+        let color = match color {
             0 => Color::Black,
             1 => Color::White,
             _ => unreachable!(),
         };
+        paint.draw(pos, color);
         match bot.step().unwrap() {
             0 => dir.turn_left(),
             1 => dir.turn_right(),
@@ -43,23 +45,24 @@ fn part1(memory: Vec<i64>) -> usize {
     }
 }
 
-fn part2(memory: Vec<i64>) -> [u8; 8] {
+fn part2(memory: Vec<i64>) -> String {
     let mut bot = Computer::new(memory);
-    let mut paint = Grid::new(Color::Black);
+    let mut paint = Renderer::new(Color::Black);
     let mut pos = Pos::origin();
     let mut dir = Dir::North;
-    paint[pos] = Color::White;
+    paint.draw(pos, Color::White);
 
     loop {
         if bot.step().is_halt() {
             break;
         }
-        let input = (paint[pos] == Color::White) as i64;
-        paint[pos] = match bot.step_with(input).unwrap() {
+        let input = (paint.grid[pos] == Color::White) as i64;
+        let color = match bot.step_with(input).unwrap() {
             0 => Color::Black,
             1 => Color::White,
             _ => unreachable!(),
         };
+        paint.draw(pos, color);
         match bot.step().unwrap() {
             0 => dir.turn_left(),
             1 => dir.turn_right(),
@@ -68,29 +71,10 @@ fn part2(memory: Vec<i64>) -> [u8; 8] {
         pos.move_to(dir);
     }
 
-    let mut chars = [0; 8];
-
-    for (i, &offset) in [1, 6, 11, 16, 21, 26, 31, 36].iter().enumerate() {
-        let mut pixels = [false; 5];
-        for (i, &(x, y)) in [(2, 0), (3, 0), (1, 3), (3, 3), (3, 5)].iter().enumerate() {
-            pixels[i] = paint[Pos { x: x + offset, y }] == Color::White;
-        }
-        chars[i] = identify(pixels);
-    }
-
-    chars
-}
-
-const CHARS: [u8; 32] = [
-    b'Y', b'C', b' ', b'F', b' ', b'P', b' ', b' ', b' ', b'B', b'U', b'J', b' ', b' ', b' ', b' ',
-    b'L', b' ', b'K', b'E', b' ', b'R', b' ', b'Z', b' ', b'G', b'H', b' ', b' ', b'A', b' ', b' ',
-];
-
-fn identify([a, b, c, d, e]: [bool; 5]) -> u8 {
-    CHARS[a as usize + 2 * b as usize + 4 * c as usize + 8 * d as usize + 16 * e as usize]
+    ocr(&paint.grid, |&color| color == Color::White).unwrap()
 }
 
-pub fn solve(input: &str) -> (usize, [u8; 8]) {
+pub fn solve(input: &str) -> (usize, String) {
     let memory = intcode::parser().parse_to_end(&input).unwrap();
     (part1(memory.clone()), part2(memory))
 }
@@ -100,7 +84,7 @@ async fn test() -> Result<(), InputError> {
     let input = get_input(2019, 11).await?;
     let memory = intcode::parser().parse_to_end(&input).unwrap();
     assert_eq!(part1(memory.clone()), 1883);
-    assert_eq!(part2(memory), *b"APUGURFH");
+    assert_eq!(part2(memory), "APUGURFH");
     Ok(())
 }
 
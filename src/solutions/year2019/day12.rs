@@ -1,58 +1,61 @@
 use super::*;
 
-#[derive(Copy, Clone, PartialEq)]
-struct Axis {
-    p: i32,
-    v: i32,
+#[derive(Copy, Clone, PartialEq, Eq)]
+struct Moon {
+    pos: Pos3,
+    vel: Pos3,
 }
 
-impl Axis {
-    fn new(p: i32) -> Self {
-        Self { p, v: 0 }
+impl Moon {
+    fn new(pos: Pos3) -> Self {
+        Self {
+            pos,
+            vel: Pos3::origin(),
+        }
     }
 
-    fn apply_gravity(&mut self, other: Self) {
-        self.v += (other.p - self.p).signum();
+    fn apply_gravity(&mut self, other: Moon) {
+        self.vel += (other.pos - self.pos).signum();
     }
 
     fn step(&mut self) {
-        self.p += self.v;
+        self.pos += self.vel;
     }
-}
 
-#[derive(Copy, Clone, PartialEq)]
-struct Moon {
-    axes: [Axis; 3],
-}
+    fn energy(self) -> i32 {
+        let origin = Pos3::origin();
+        self.pos.manhattan_distance(origin) * self.vel.manhattan_distance(origin)
+    }
 
-impl Moon {
-    fn apply_gravity(&mut self, other: Moon) {
-        for (a, &b) in self.axes.iter_mut().zip(&other.axes) {
-            a.apply_gravity(b);
-        }
+    /// The moon with every axis but `i` zeroed out. The axes don't affect each other, so
+    /// this moves like the moon does along `i`.
+    fn axis(self, i: usize) -> Self {
+        let mut axis = Self::new(Pos3::origin());
+        axis.pos[i] = self.pos[i];
+        axis.vel[i] = self.vel[i];
+        axis
     }
+}
 
-    fn step(&mut self) {
-        for a in &mut self.axes {
-            a.step();
+fn step(moons: &mut [Moon; 4]) {
+    for i in 0..4 {
+        for j in 0..4 {
+            if i != j {
+                moons[i].apply_gravity(moons[j]);
+            }
         }
     }
 
-    fn energy(self) -> i32 {
-        let Moon { axes: [x, y, z] } = self;
-        (x.p.abs() + y.p.abs() + z.p.abs()) * (x.v.abs() + y.v.abs() + z.v.abs())
+    for moon in moons {
+        moon.step();
     }
 }
 
 fn parser<'a>() -> impl Parser<&'a str, Output = [Moon; 4]> {
     let moon = parser::i32()
         .sep_by(rubbish(), |iter| {
-            let axes = [
-                Axis::new(iter.next()?),
-                Axis::new(iter.next()?),
-                Axis::new(iter.next()?),
-            ];
-            Some(Moon { axes })
+            let pos = PosN([iter.next()?, iter.next()?, iter.next()?]);
+            Some(Moon::new(pos))
         })
         .between(rubbish(), rubbish());
     moon.sep_by(newline(), |iter| {
@@ -62,46 +65,23 @@ fn parser<'a>() -> impl Parser<&'a str, Output = [Moon; 4]> {
 
 fn part1(mut moons: [Moon; 4]) -> i32 {
     for _ in 0..1000 {
-        for i in 0..4 {
-            for j in 0..4 {
-                if i != j {
-                    moons[i].apply_gravity(moons[j]);
-                }
-            }
-        }
-
-        for moon in &mut moons {
-            moon.step();
-        }
+        step(&mut moons);
     }
     moons.iter().map(|moon| moon.energy()).sum()
 }
 
 fn part2([a, b, c, d]: [Moon; 4]) -> u64 {
-    let x_len = cycle_len([a.axes[0], b.axes[0], c.axes[0], d.axes[0]]);
-    let y_len = cycle_len([a.axes[1], b.axes[1], c.axes[1], d.axes[1]]);
-    let z_len = cycle_len([a.axes[2], b.axes[2], c.axes[2], d.axes[2]]);
-    lcm(lcm(x_len, y_len), z_len)
+    let axis_len = |i| cycle_len([a.axis(i), b.axis(i), c.axis(i), d.axis(i)]);
+    lcm(lcm(axis_len(0), axis_len(1)), axis_len(2))
 }
 
-fn cycle_len(mut axes: [Axis; 4]) -> u64 {
-    let initial = axes;
-    for step in 1.. {
-        for i in 0..4 {
-            for j in 0..4 {
-                if i != j {
-                    axes[i].apply_gravity(axes[j]);
-                }
-            }
-        }
-        for axis in &mut axes {
-            axis.step();
-        }
-        if axes == initial {
-            return step;
-        }
-    }
-    unreachable!()
+fn cycle_len(moons: [Moon; 4]) -> u64 {
+    let len = period(moons, |moons| {
+        let mut moons = *moons;
+        step(&mut moons);
+        moons
+    });
+    len as u64
 }
 
 pub fn solve(input: &str) -> (i32, u64) {
@@ -1,64 +1,29 @@
 use super::*;
+use arcade::*;
 use intcode::prelude::*;
 
-fn part1(arcade: &mut Computer) -> u32 {
-    let mut block_count = 0;
-
-    while let Interrupt::Output(_x) = arcade.step() {
-        let _y = arcade.step();
-        if arcade.step().unwrap() == 2 {
-            block_count += 1;
-        }
-    }
-
-    block_count
+fn part1(memory: Vec<i64>) -> usize {
+    let mut arcade = Arcade::new(Computer::new(memory));
+    arcade.play(follow_ball);
+    arcade.count(Tile::Block)
 }
 
-fn part2(mut arcade: Computer) -> i64 {
-    arcade.memory[0] = 2;
-
-    let mut ball_x: i64 = 0;
-    let mut ball_y: i64 = 0;
-    let mut paddle_x: i64 = 0;
-    let mut output = 0;
-
-    loop {
-        let x = match arcade.step_with((ball_x - paddle_x).signum()).output() {
-            None => return output,
-            Some(x) => x,
-        };
-        let _y = arcade.step();
-        let z = arcade.step().unwrap();
-
-        if x == -1 {
-            println!("{} points, ball_x = {},{}", z - output, ball_x, ball_y);
-            output = z;
-        } else {
-            match z {
-                3 => paddle_x = x,
-                4 => {
-                    ball_x = x;
-                    ball_y = _y.unwrap();
-                }
-                _ => {}
-            };
-        }
-    }
+fn part2(mut memory: Vec<i64>) -> i64 {
+    memory[0] = 2;
+    Arcade::new(Computer::new(memory)).play(follow_ball)
 }
 
-pub fn solve(input: &str) -> (u32, i64) {
+pub fn solve(input: &str) -> (usize, i64) {
     let memory = intcode::parser().parse_to_end(&input).unwrap();
-    let mut arcade = Computer::new(memory);
-    (part1(&mut arcade), part2(arcade))
+    (part1(memory.clone()), part2(memory))
 }
 
 #[async_std::test]
 async fn test() -> Result<(), InputError> {
     let input = get_input(2019, 13).await?;
     let memory = intcode::parser().parse_to_end(&input).unwrap();
-    let mut arcade = Computer::new(memory);
-    assert_eq!(part1(&mut arcade), 363);
-    assert_eq!(part2(arcade), 17_159);
+    assert_eq!(part1(memory.clone()), 363);
+    assert_eq!(part2(memory), 17_159);
     Ok(())
 }
 
@@ -69,19 +69,10 @@ pub fn solve(input: &str) -> (usize, usize) {
         }
     }
 
-    seen.remove(&oxygen);
-    let mut part2 = 0;
-
-    let mut states = vec![(oxygen, 0)];
-    while let Some((pos, dist)) = states.pop() {
-        part2 = cmp::max(part2, dist);
-        for dir in Dir::all() {
-            let n = pos.moving_to(dir);
-            if seen.remove(&n) {
-                states.push((n, dist + 1));
-            }
-        }
-    }
+    let part2 = flood(iter::once(oxygen), |pos| seen.contains(&pos))
+        .last()
+        .unwrap()
+        .1;
 
     (part1, part2)
 }
@@ -80,19 +80,11 @@ pub fn solve(input: &str) -> (i32, i64) {
     for (line, y) in visual.lines().zip(0..) {
         for (b, x) in line.bytes().zip(0..) {
             let pos = Pos { x, y };
-            let dir = match b {
-                b'.' => continue,
-                b'#' => {
-                    grid[pos] = true;
-                    continue;
-                }
-                b'^' => Dir::North,
-                b'v' => Dir::South,
-                b'<' => Dir::West,
-                b'>' => Dir::East,
-                _ => unreachable!(),
-            };
-            start = Some((pos, dir));
+            match b {
+                b'.' => {}
+                b'#' => grid[pos] = true,
+                _ => start = Some((pos, Dir::from_char(b as char).unwrap())),
+            }
         }
     }
 
@@ -45,91 +45,64 @@ enum Location {
 }
 
 fn data(input: &str) -> Data {
-    let mut entrance = Pos::origin();
-    let mut grid = Vec::new();
-    let width = input.bytes().position(|b| b == b'\n').unwrap();
-
-    let index_of = |Pos { x, y }| x as usize + y as usize * width;
-
-    for (row, y) in input.lines().zip(0..) {
-        for (c, x) in row.bytes().zip(0..) {
-            let pos = Pos { x, y };
-            let tile = match c {
-                b'.' => Tile::Path,
-                b'#' => Tile::Wall,
-                b'@' => {
-                    entrance = pos;
-                    Tile::Path
-                }
-                b'a'..=b'z' => Tile::Key((c - b'a') as u32),
-                b'A'..=b'Z' => Tile::Door((c - b'A') as u32),
-                _ => unreachable!(),
-            };
-            grid.push(tile);
-        }
-    }
+    let (mut grid, markers) = DenseGrid::parse(
+        input,
+        |c| c == b'@' || c.is_ascii_lowercase(),
+        |c| match c {
+            b'.' | b'@' => Tile::Path,
+            b'#' => Tile::Wall,
+            b'a'..=b'z' => Tile::Key((c - b'a') as u32),
+            b'A'..=b'Z' => Tile::Door((c - b'A') as u32),
+            _ => unreachable!(),
+        },
+    );
+    let entrance = markers[b'@'];
 
     for pos in entrance.neighbors() {
-        grid[index_of(pos)] = Tile::Wall;
+        grid[pos] = Tile::Wall;
     }
 
+    let entrances: Vec<Pos> = [(-1, -1), (-1, 1), (1, -1), (1, 1)]
+        .iter()
+        .map(|&(x, y)| entrance + Pos { x, y })
+        .collect();
+    let keys: Vec<(u8, Pos)> = markers.iter().filter(|&(c, _)| c != b'@').collect();
+    let points: Vec<Pos> = entrances
+        .iter()
+        .copied()
+        .chain(keys.iter().map(|&(_, pos)| pos))
+        .collect();
+    let graph = MazeGraph::new(
+        &grid,
+        &points,
+        |&tile| tile != Tile::Wall,
+        |&tile| match tile {
+            Tile::Door(door) => Some(door),
+            _ => None,
+        },
+    );
+    let key_at = |i: usize| (keys[i - entrances.len()].0 - b'a') as usize;
+
     let mut distance = [[0; 26]; 26];
     let mut distance_to_entrance = [(0, Pos::origin()); 26];
     let mut blocked_by = [Mask::empty(); 26];
     let mut all_keys = Mask::empty();
     let mut quadrants = [Mask::empty(); 4];
 
-    for (i, &(dx, dy)) in [(-1, -1), (-1, 1), (1, -1), (1, 1)].iter().enumerate() {
-        let entrance = Pos {
-            x: entrance.x + dx,
-            y: entrance.y + dy,
-        };
-        let mut stack = vec![(entrance, 0, Mask::empty())];
-        let mut seen = HashSet::new();
-        let mut branches = Vec::<(u32, i32, i32)>::new(); // (key, distance to entrance, distance to branch)
-        let mut prev_dist = 0;
-        let mut quadrant = Mask::empty();
-
-        while let Some((pos, dist, mut doors)) = stack.pop() {
-            match grid[index_of(pos)] {
-                Tile::Wall => continue,
-                Tile::Path => {}
-                Tile::Key(key) => {
-                    quadrant.insert(key);
-                    blocked_by[key as usize] = doors;
-                    distance_to_entrance[key as usize] = (dist, entrance);
-                    for &(other, other_dist, branch) in &branches {
-                        let between = dist + other_dist - 2 * branch;
-                        distance[key as usize][other as usize] = between;
-                        distance[other as usize][key as usize] = between;
-                    }
-                    branches.push((key, dist, dist));
-                }
-                Tile::Door(door) => {
-                    doors.insert(door);
-                }
-            }
-
-            let branch = dist - 1;
-            if branch != prev_dist {
-                // bracktracking
-                for (_, _, key_branch) in &mut branches {
-                    *key_branch = cmp::min(*key_branch, branch);
-                }
-            }
-            prev_dist = dist;
-
-            for neighbor in pos.non_neg_neighbors() {
-                if seen.contains(&neighbor) {
-                    continue;
-                }
-                seen.insert(neighbor);
-                stack.push((neighbor, dist + 1, doors));
-            }
+    for (i, &entrance) in entrances.iter().enumerate() {
+        for edge in graph.edges(i) {
+            let key = key_at(edge.to);
+            quadrants[i].insert(key as u32);
+            blocked_by[key] = edge.crossed;
+            distance_to_entrance[key] = (edge.distance as i32, entrance);
         }
+        all_keys.add(quadrants[i]);
+    }
 
-        all_keys.add(quadrant);
-        quadrants[i] = quadrant;
+    for i in entrances.len()..graph.len() {
+        for edge in graph.edges(i).iter().filter(|e| e.to >= entrances.len()) {
+            distance[key_at(i)][key_at(edge.to)] = edge.distance as i32;
+        }
     }
 
     for i in 0..26 {
@@ -153,13 +126,17 @@ fn data(input: &str) -> Data {
     }
 }
 
-fn part1(data: &Data) -> i32 {
-    #[derive(Eq, PartialEq, Copy, Clone, Hash)]
-    struct Node {
-        location: Location,
-        not_collected: Mask<u32>,
-    }
+/// The distances from `Location::Entrance` are measured from the nearest of the four
+/// entrances of part 2, which are two steps away from the entrance of part 1.
+const ENTRANCE_OFFSET: i32 = 2;
+
+#[derive(Eq, PartialEq, Copy, Clone, Hash)]
+struct Node {
+    location: Location,
+    not_collected: Mask<u32>,
+}
 
+fn part1(data: &Data) -> i32 {
     let mut map = HashMap::new();
     let mut new = HashMap::new();
     map.insert(
@@ -167,7 +144,7 @@ fn part1(data: &Data) -> i32 {
             location: Location::Entrance,
             not_collected: data.all_keys,
         },
-        2,
+        ENTRANCE_OFFSET,
     );
 
     for _ in 0..26 {
@@ -192,6 +169,43 @@ fn part1(data: &Data) -> i32 {
     map.values().copied().min().unwrap()
 }
 
+/// The search of part 1 as a shortest path problem, to compare the search backends.
+struct KeySearch<'a>(&'a Data);
+
+impl Dijkstra for KeySearch<'_> {
+    type Node = Node;
+    type Cost = i32;
+
+    fn start(&self) -> Node {
+        Node {
+            location: Location::Entrance,
+            not_collected: self.0.all_keys,
+        }
+    }
+
+    fn is_goal(&self, node: &Node) -> bool {
+        node.not_collected.is_empty()
+    }
+
+    fn neighbors(&self, &node: &Node, mut add: impl FnMut(Node, i32)) {
+        let data = self.0;
+        for key in node.not_collected.iter() {
+            if data.doors_blocking_key(key).intersects(node.not_collected) {
+                continue;
+            }
+
+            let mut cost = data.distance_between(node.location, key);
+            if node.location == Location::Entrance {
+                cost += ENTRANCE_OFFSET;
+            }
+            let mut next = node;
+            next.location = Location::Key(key);
+            next.not_collected.remove(key);
+            add(next, cost);
+        }
+    }
+}
+
 fn part2(data: &Data) -> i32 {
     #[derive(Eq, PartialEq, Copy, Clone, Hash)]
     struct Node {
@@ -248,6 +262,8 @@ async fn test() -> Result<(), InputError> {
     let data = data(&input);
     assert_eq!(part1(&data), 5858);
     assert_eq!(part2(&data), 2144);
+    assert_eq!(KeySearch(&data).run(), Some(5858));
+    assert_eq!(Bucketed(KeySearch(&data)).run(), Some(5858));
     Ok(())
 }
 
@@ -270,6 +286,20 @@ mod benches {
         b.iter(|| part1(&data(&input)));
     }
 
+    #[bench]
+    fn bench_part1_heap(b: &mut Bencher) {
+        let input = futures::executor::block_on(get_input(2019, 18)).unwrap();
+        let data = data(&input);
+        b.iter(|| KeySearch(&data).run());
+    }
+
+    #[bench]
+    fn bench_part1_buckets(b: &mut Bencher) {
+        let input = futures::executor::block_on(get_input(2019, 18)).unwrap();
+        let data = data(&input);
+        b.iter(|| Bucketed(KeySearch(&data)).run());
+    }
+
     #[bench]
     fn bench_part2(b: &mut Bencher) {
         let input = futures::executor::block_on(get_input(2019, 18)).unwrap();
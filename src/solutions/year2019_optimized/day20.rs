@@ -1,6 +1,5 @@
 use super::*;
 use mask::*;
-use search_algs::*;
 
 // IMPORTANT:
 // don't bother reading this code, it's not supposed to make any sense
@@ -27,7 +26,7 @@ struct Data {
     end: usize,
     distances: Vec<u32>,
     portals: Vec<PortalInfo>,
-    min_dist: u32,
+    level_cost: u32,
 }
 
 impl Data {
@@ -275,12 +274,21 @@ fn data(input: &str) -> Data {
         }
     }
 
-    let mut min_dist = u32::max_value();
+    // going up `n` levels costs at least `n * level_cost`, so `depth * level_cost` never
+    // drops by more than the cost of an edge
+    let mut level_cost = None;
     for i in 0..num_portals {
         for j in portal_info[i].accessible_portals.iter() {
             let j = j as usize;
-            if portal_info[i].depth_change == 1 && portal_info[j].depth_change == -1 {
-                min_dist = cmp::min(min_dist, distances[i * num_portals + j]);
+            let dist = distances[i + j * num_portals];
+            let PortalInfo {
+                cost_of_teleportation: cost,
+                depth_change: delta,
+                ..
+            } = portal_info[j];
+            if dist > 0 && delta < 0 {
+                let cost = (dist + cost) / -delta as u32;
+                level_cost = Some(level_cost.map_or(cost, |min| cmp::min(min, cost)));
             }
         }
     }
@@ -290,57 +298,78 @@ fn data(input: &str) -> Data {
         end,
         distances,
         portals: portal_info,
-        min_dist,
+        level_cost: level_cost.unwrap_or(0),
+    }
+}
+
+struct Maze<'a>(&'a Data);
+
+impl Dijkstra for Maze<'_> {
+    type Node = usize;
+    type Cost = u32;
+
+    fn start(&self) -> usize {
+        self.0.start
+    }
+
+    fn is_goal(&self, &i: &usize) -> bool {
+        i == self.0.end
+    }
+
+    fn neighbors(&self, &i: &usize, mut add: impl FnMut(usize, u32)) {
+        let data = self.0;
+        for j in data.portals_accessible_from(i) {
+            let j = j as usize;
+            let dist = data.distance_between(i, j);
+            if dist > 0 {
+                add(data.sibling_portal_of(j), dist + data.cost_of(j));
+            }
+        }
+    }
+}
+
+struct RecursiveMaze<'a>(&'a Data);
+
+impl Dijkstra for RecursiveMaze<'_> {
+    type Node = (usize, i32);
+    type Cost = u32;
+
+    fn start(&self) -> Self::Node {
+        (self.0.start, 0)
+    }
+
+    fn is_goal(&self, &(i, depth): &Self::Node) -> bool {
+        i == self.0.end && depth == 0
+    }
+
+    fn neighbors(&self, &(i, depth): &Self::Node, mut add: impl FnMut(Self::Node, u32)) {
+        let data = self.0;
+        for j in data.portals_accessible_from(i) {
+            let j = j as usize;
+            let dist = data.distance_between(i, j);
+            let delta = data.depth_change_of(j);
+            let new_depth = depth + delta;
+            let is_blocked = delta == 0 && depth > 0;
+            if !is_blocked && dist > 0 && new_depth >= 0 {
+                add(
+                    (data.sibling_portal_of(j), new_depth),
+                    dist + data.cost_of(j),
+                );
+            }
+        }
+    }
+
+    fn heuristic(&self, &(_, depth): &Self::Node) -> u32 {
+        depth as u32 * self.0.level_cost
     }
 }
 
 fn part1(data: &Data) -> u32 {
-    let (_, len) = dijkstra(
-        &data.start,
-        |&i| {
-            data.portals_accessible_from(i).filter_map(move |j| {
-                let j = j as usize;
-                let dist = data.distance_between(i, j);
-                let sibling = data.sibling_portal_of(j);
-                let cost = data.cost_of(j);
-                if dist > 0 {
-                    Some((sibling, dist + cost))
-                } else {
-                    None
-                }
-            })
-        },
-        |&i| i == data.end,
-    )
-    .unwrap();
-    len
+    Maze(data).run().unwrap()
 }
 
 fn part2(data: &Data) -> u32 {
-    let (_, len) = astar(
-        &(data.start, 0),
-        |&(i, depth)| {
-            data.portals_accessible_from(i).filter_map(move |j| {
-                let j = j as usize;
-                let dist = data.distance_between(i, j);
-                let delta = data.depth_change_of(j);
-                let new_depth = depth + delta;
-                let is_blocked = delta == 0 && depth > 0;
-                if !is_blocked && dist > 0 && new_depth >= 0 {
-                    Some((
-                        (data.sibling_portal_of(j), new_depth),
-                        dist + data.cost_of(j),
-                    ))
-                } else {
-                    None
-                }
-            })
-        },
-        |&(_, depth)| depth as u32 * (data.min_dist + 1),
-        |&(i, depth)| i == data.end && depth == 0,
-    )
-    .unwrap();
-    len
+    RecursiveMaze(data).run().unwrap()
 }
 
 pub fn solve(input: &str) -> (u32, u32) {
@@ -354,6 +383,8 @@ async fn test() -> Result<(), InputError> {
     let data = data(&input);
     assert_eq!(part1(&data), 410);
     assert_eq!(part2(&data), 5084);
+    assert_eq!(Bucketed(Maze(&data)).run(), Some(410));
+    assert_eq!(Bucketed(RecursiveMaze(&data)).run(), Some(5084));
     Ok(())
 }
 
@@ -373,10 +404,31 @@ mod benches {
         });
     }
 
+    #[bench]
+    fn bench_part1(b: &mut Bencher) {
+        let input = futures::executor::block_on(get_input(2019, 20)).unwrap();
+        let data = data(&input);
+        b.iter(|| part1(&data));
+    }
+
     #[bench]
     fn bench_part2(b: &mut Bencher) {
         let input = futures::executor::block_on(get_input(2019, 20)).unwrap();
         let data = data(&input);
         b.iter(|| part2(&data));
     }
+
+    #[bench]
+    fn bench_part1_buckets(b: &mut Bencher) {
+        let input = futures::executor::block_on(get_input(2019, 20)).unwrap();
+        let data = data(&input);
+        b.iter(|| Bucketed(Maze(&data)).run().unwrap());
+    }
+
+    #[bench]
+    fn bench_part2_buckets(b: &mut Bencher) {
+        let input = futures::executor::block_on(get_input(2019, 20)).unwrap();
+        let data = data(&input);
+        b.iter(|| Bucketed(RecursiveMaze(&data)).run().unwrap());
+    }
 }
@@ -1,33 +1,9 @@
 use super::*;
 use mask::*;
 
-const P1_NEIGHBORS: [Mask<u32>; 25] = [
-    Mask(0b_00000_00000_00000_00001_00010),
-    Mask(0b_00000_00000_00000_00010_00101),
-    Mask(0b_00000_00000_00000_00100_01010),
-    Mask(0b_00000_00000_00000_01000_10100),
-    Mask(0b_00000_00000_00000_10000_01000),
-    Mask(0b_00000_00000_00001_00010_00001),
-    Mask(0b_00000_00000_00010_00101_00010),
-    Mask(0b_00000_00000_00100_01010_00100),
-    Mask(0b_00000_00000_01000_10100_01000),
-    Mask(0b_00000_00000_10000_01000_10000),
-    Mask(0b_00000_00001_00010_00001_00000),
-    Mask(0b_00000_00010_00101_00010_00000),
-    Mask(0b_00000_00100_01010_00100_00000),
-    Mask(0b_00000_01000_10100_01000_00000),
-    Mask(0b_00000_10000_01000_10000_00000),
-    Mask(0b_00001_00010_00001_00000_00000),
-    Mask(0b_00010_00101_00010_00000_00000),
-    Mask(0b_00100_01010_00100_00000_00000),
-    Mask(0b_01000_10100_01000_00000_00000),
-    Mask(0b_10000_01000_10000_00000_00000),
-    Mask(0b_00010_00001_00000_00000_00000),
-    Mask(0b_00101_00010_00000_00000_00000),
-    Mask(0b_01010_00100_00000_00000_00000),
-    Mask(0b_10100_01000_00000_00000_00000),
-    Mask(0b_01000_10000_00000_00000_00000),
-];
+fn bugs() -> impl Fn(bool, usize) -> bool {
+    birth_survival(&[1, 2], &[1])
+}
 
 /// part 2 bitmask layout:
 ///      |                                     |
@@ -117,17 +93,13 @@ const P2_NEIGHBORS: [Mask<u32>; 44] = [
 ];
 
 fn part1(input: &str) -> u32 {
+    let automaton = MaskAutomaton::grid(5, 5, &VON_NEUMANN, bugs());
     let mut mask = p1_parse(input);
-    let mut seen = Vec::new();
-
-    loop {
-        if seen.iter().any(|&m| m == mask) {
-            return mask.0;
-        } else {
-            seen.push(mask);
-            mask = p1_evolve(mask);
-        }
+    let mut detector = CycleDetector::new();
+    while detector.push(mask).is_none() {
+        mask = automaton.step(mask);
     }
+    mask.0
 }
 
 fn p1_parse(input: &str) -> Mask<u32> {
@@ -142,23 +114,11 @@ fn p1_parse(input: &str) -> Mask<u32> {
     mask
 }
 
-fn p1_evolve(mask: Mask<u32>) -> Mask<u32> {
-    let mut one = Mask::empty();
-    let mut two = Mask::empty();
-    let mut three = Mask::empty();
-
-    for i in mask.iter() {
-        let n = P1_NEIGHBORS[i as usize];
-        three |= two & n;
-        two |= one & n;
-        one |= n;
-    }
-
-    one & !((!mask | two) & (mask | three))
-}
-
 fn part2(input: &str) -> u32 {
+    let neighbors = P2_NEIGHBORS.iter().map(|&n| Mask::from(n)).collect();
+    let automaton = MaskAutomaton::table(neighbors, bugs());
     let mask = p2_parse(input);
+    // every level gets its own mask, whose 44 bits (see `P2_NEIGHBORS`) fit in a `u64`
     let mut layers = [Mask::<u64>::empty(); 201];
     layers[100] = mask;
 
@@ -169,7 +129,7 @@ fn part2(input: &str) -> u32 {
             layers[i + 1] |= (layers[i] & Mask((1 << 20) - (1 << 16))) << 24;
         }
         for layer in &mut layers[100 - delta..=100 + delta] {
-            *layer = p2_evolve(*layer);
+            *layer = automaton.step(*layer);
         }
     }
 
@@ -193,25 +153,6 @@ fn p2_parse(input: &str) -> Mask<u64> {
     mask
 }
 
-fn p2_evolve(mask: Mask<u64>) -> Mask<u64> {
-    let mut one = Mask::empty();
-    let mut two = Mask::empty();
-    let mut three = Mask::empty();
-
-    for i in mask.iter() {
-        let n = P2_NEIGHBORS[i as usize];
-        three |= two & n;
-        two |= one & n;
-        one |= n;
-    }
-
-    let one = Mask::<u64>::from(one);
-    let two = Mask::<u64>::from(two);
-    let three = Mask::<u64>::from(three);
-
-    one & !((!mask | two) & (mask | three))
-}
-
 pub fn solve(input: &str) -> (u32, u32) {
     (part1(input), part2(input))
 }
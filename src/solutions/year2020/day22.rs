@@ -1,3 +1,4 @@
+use crate::utils::CycleDetector;
 use std::collections::VecDeque;
 
 fn fight1(p1: impl Iterator<Item = u32>, p2: impl Iterator<Item = u32>) -> (bool, VecDeque<u32>) {
@@ -28,7 +29,7 @@ fn fight2(p1: impl Iterator<Item = u32>, p2: impl Iterator<Item = u32>) -> (bool
     let mut p1: VecDeque<_> = p1.collect();
     let mut p2: VecDeque<_> = p2.collect();
 
-    let mut seen = fxhash::FxHashSet::default();
+    let mut seen = CycleDetector::with_hasher(fxhash::FxBuildHasher::default());
 
     while !p1.is_empty() && !p2.is_empty() {
         let mut array = [0; 51];
@@ -41,7 +42,7 @@ fn fight2(p1: impl Iterator<Item = u32>, p2: impl Iterator<Item = u32>) -> (bool
             array[i + p1.len() + 1] = n as u8;
         }
 
-        if !seen.insert(array) {
+        if seen.push(array).is_some() {
             return (true, p1);
         }
 
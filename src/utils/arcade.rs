@@ -0,0 +1,234 @@
+use super::{
+    image::{Frames, Rgb},
+    intcode::prelude::*,
+    Drawable, Grid, Pos,
+};
+use std::{io, path::Path};
+
+type OnFrame<'a, T> = Box<dyn FnMut(usize, &Grid<T>) -> io::Result<()> + 'a>;
+
+pub struct Renderer<'a, T> {
+    pub grid: Grid<T>,
+    every: usize,
+    outputs: usize,
+    frames: usize,
+    on_frame: OnFrame<'a, T>,
+    error: Option<io::Error>,
+}
+
+impl<'a, T: Clone> Renderer<'a, T> {
+    /// A renderer that only collects tiles and never produces frames.
+    pub fn new(default: T) -> Self {
+        Self::every(default, 0, |_, _| Ok(()))
+    }
+
+    /// Calls `on_frame` with the frame number and the current grid after every `n` outputs.
+    /// Once it fails, no more frames are produced, and `finish` returns the error.
+    pub fn every(
+        default: T,
+        n: usize,
+        on_frame: impl FnMut(usize, &Grid<T>) -> io::Result<()> + 'a,
+    ) -> Self {
+        Self {
+            grid: Grid::new(default),
+            every: n,
+            outputs: 0,
+            frames: 0,
+            on_frame: Box::new(on_frame),
+            error: None,
+        }
+    }
+
+    pub fn draw(&mut self, pos: Pos, tile: T) {
+        self.grid[pos] = tile;
+        self.outputs += 1;
+        if self.every != 0 && self.outputs % self.every == 0 {
+            self.frame();
+        }
+    }
+
+    pub fn frame(&mut self) {
+        if self.error.is_some() {
+            return;
+        }
+        match (self.on_frame)(self.frames, &self.grid) {
+            Ok(()) => self.frames += 1,
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames
+    }
+
+    /// Returns the number of frames produced, or the error that stopped them.
+    pub fn finish(&mut self) -> io::Result<usize> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(self.frames),
+        }
+    }
+}
+
+pub fn ascii<T>(grid: &Grid<T>, f: impl Fn(&T) -> char) -> String {
+    grid.render(f).to_string()
+}
+
+/// Returns a frame callback that writes each frame to `dir/frame_00000.ppm` and onwards.
+pub fn ppm_sequence<'a, T>(
+    dir: impl AsRef<Path>,
+    color: impl Fn(&T) -> Rgb + 'a,
+) -> io::Result<impl FnMut(usize, &Grid<T>) -> io::Result<()> + 'a> {
+    let mut frames = Frames::new(dir)?;
+    Ok(move |_, grid: &Grid<T>| frames.record(grid, &color))
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Tile {
+    Empty,
+    Wall,
+    Block,
+    Paddle,
+    Ball,
+}
+
+impl Tile {
+    pub fn new(id: i64) -> Self {
+        match id {
+            0 => Self::Empty,
+            1 => Self::Wall,
+            2 => Self::Block,
+            3 => Self::Paddle,
+            4 => Self::Ball,
+            _ => panic!("invalid tile id: {}", id),
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Self::Empty => ' ',
+            Self::Wall => '#',
+            Self::Block => '=',
+            Self::Paddle => '-',
+            Self::Ball => 'o',
+        }
+    }
+
+    pub fn to_rgb(self) -> Rgb {
+        match self {
+            Self::Empty => [0, 0, 0],
+            Self::Wall => [128, 128, 128],
+            Self::Block => [200, 80, 40],
+            Self::Paddle => [40, 120, 220],
+            Self::Ball => [255, 255, 255],
+        }
+    }
+}
+
+pub struct Arcade<'a> {
+    pub computer: Computer,
+    pub screen: Renderer<'a, Tile>,
+    pub score: i64,
+    pub ball: Pos,
+    pub paddle: Pos,
+}
+
+impl<'a> Arcade<'a> {
+    pub fn new(computer: Computer) -> Self {
+        Self::with_renderer(computer, Renderer::new(Tile::Empty))
+    }
+
+    pub fn with_renderer(computer: Computer, screen: Renderer<'a, Tile>) -> Self {
+        Self {
+            computer,
+            screen,
+            score: 0,
+            ball: Pos::origin(),
+            paddle: Pos::origin(),
+        }
+    }
+
+    /// Runs the game until it halts, asking `joystick` for a tilt of -1, 0 or 1
+    /// whenever input is needed, and returns the final score.
+    pub fn play(&mut self, mut joystick: impl FnMut(&Self) -> i64) -> i64 {
+        loop {
+            let x = match self.computer.step() {
+                Interrupt::Halt => return self.score,
+                Interrupt::Output(x) => x,
+                Interrupt::WaitingForInput => {
+                    let tilt = joystick(self);
+                    match self.computer.step_with(tilt).output() {
+                        None => return self.score,
+                        Some(x) => x,
+                    }
+                }
+            };
+            let y = self.computer.step().unwrap();
+            let z = self.computer.step().unwrap();
+
+            if (x, y) == (-1, 0) {
+                self.score = z;
+                continue;
+            }
+
+            let pos = Pos {
+                x: x as i32,
+                y: y as i32,
+            };
+            let tile = Tile::new(z);
+            match tile {
+                Tile::Paddle => self.paddle = pos,
+                Tile::Ball => self.ball = pos,
+                _ => {}
+            }
+            self.screen.draw(pos, tile);
+        }
+    }
+
+    pub fn count(&self, tile: Tile) -> usize {
+        self.screen.grid.iter().filter(|&(_, &t)| t == tile).count()
+    }
+}
+
+/// A joystick policy that keeps the paddle underneath the ball.
+pub fn follow_ball(arcade: &Arcade<'_>) -> i64 {
+    (arcade.ball.x - arcade.paddle.x).signum() as i64
+}
+
+#[test]
+fn test_arcade() {
+    // draws a wall, a paddle and a ball, then sets the score
+    let memory = vec![
+        104, 0, 104, 0, 104, 1, 104, 2, 104, 1, 104, 3, 104, 3, 104, 1, 104, 4, 104, -1, 104, 0,
+        104, 42, 99,
+    ];
+    let mut frames = Vec::new();
+    let renderer = Renderer::every(Tile::Empty, 1, |_, grid: &Grid<Tile>| {
+        frames.push(ascii(grid, |t| t.to_char()));
+        Ok(())
+    });
+    let mut arcade = Arcade::with_renderer(Computer::new(memory), renderer);
+
+    assert_eq!(arcade.play(follow_ball), 42);
+    assert_eq!(arcade.paddle, Pos { x: 2, y: 1 });
+    assert_eq!(arcade.ball, Pos { x: 3, y: 1 });
+    assert_eq!(arcade.count(Tile::Wall), 1);
+    assert_eq!(arcade.screen.finish().unwrap(), 3);
+    drop(arcade);
+    assert_eq!(frames, vec!["#\n", "#  \n  -\n", "#   \n  -o\n"]);
+
+    // the first failed frame is the last one attempted
+    let mut attempts = 0;
+    let mut renderer = Renderer::every(Tile::Empty, 1, |n, _| {
+        attempts += 1;
+        match n {
+            0 => Ok(()),
+            _ => Err(io::Error::new(io::ErrorKind::Other, "disk full")),
+        }
+    });
+    (0..3).for_each(|x| renderer.draw(Pos { x, y: 0 }, Tile::Block));
+    assert_eq!(renderer.frame_count(), 1);
+    assert_eq!(renderer.finish().unwrap_err().to_string(), "disk full");
+    drop(renderer);
+    assert_eq!(attempts, 2);
+}
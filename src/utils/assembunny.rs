@@ -0,0 +1,506 @@
+use super::register_machine::{self, program, register, source, Execute, Flow, Machine, Step};
+use parser::prelude::*;
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+    iter,
+};
+
+pub use super::register_machine::{Diff, Listing, Register, Regs};
+
+pub type Source = register_machine::Source<i32>;
+pub type VM = Machine<Instruction, i32, 4>;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Instruction {
+    Cpy { from: Source, to: Source },
+    Inc(Source),
+    Dec(Source),
+    Jnz { test: Source, by: Source },
+    Tgl(Register),
+    Out(Source),
+}
+
+impl Instruction {
+    fn toggle(&mut self) {
+        *self = match *self {
+            Self::Cpy { from, to } => Self::Jnz { test: from, by: to },
+            Self::Inc(r) => Self::Dec(r),
+            Self::Dec(r) => Self::Inc(r),
+            Self::Jnz { test, by } => Self::Cpy { from: test, to: by },
+            Self::Tgl(r) => Self::Inc(Source::Register(r)),
+            Self::Out(s) => Self::Inc(s),
+        };
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cpy { from, to } => write!(f, "cpy {} {}", from, to),
+            Self::Inc(r) => write!(f, "inc {}", r),
+            Self::Dec(r) => write!(f, "dec {}", r),
+            Self::Jnz { test, by } => write!(f, "jnz {} {}", test, by),
+            Self::Tgl(r) => write!(f, "tgl {}", r),
+            Self::Out(s) => write!(f, "out {}", s),
+        }
+    }
+}
+
+impl Execute<i32, 4> for Instruction {
+    fn execute(self, vm: &mut VM) -> Flow<i32> {
+        match self {
+            Self::Cpy { from, to } => {
+                let value = vm.regs.read(from);
+                match to {
+                    Source::Value(_) => { /* skip */ }
+                    Source::Register(r) => *vm.regs.write(r) = value,
+                }
+            }
+            Self::Inc(Source::Register(r)) => *vm.regs.write(r) += 1,
+            Self::Dec(Source::Register(r)) => *vm.regs.write(r) -= 1,
+            Self::Inc(Source::Value(_)) | Self::Dec(Source::Value(_)) => { /* skip */ }
+            Self::Jnz { test, by } => {
+                if vm.regs.read(test) > 0 {
+                    return Flow::Jump(vm.regs.read(by));
+                }
+            }
+            Self::Tgl(r) => {
+                let at = vm.ip as i32 + vm.regs.read(Source::Register(r));
+                if at >= 0 && (at as usize) < vm.instructions.len() {
+                    vm.instructions[at as usize].toggle();
+                }
+            }
+            Self::Out(s) => return Flow::Output(vm.regs.read(s)),
+        }
+        Flow::Next
+    }
+}
+
+/// A run of instructions that can be executed in one go, recognised by `shortcuts`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Shortcut {
+    /// `inc to`, `dec from`, `jnz from -2`
+    Add { to: Register, from: Register },
+    /// `cpy from tmp`, an add loop of `tmp` into `to`, `dec counter`, `jnz counter -5`
+    Multiply {
+        to: Register,
+        from: Source,
+        tmp: Register,
+        counter: Register,
+    },
+}
+
+impl Shortcut {
+    /// Applies the shortcut and returns the number of instructions it replaces, or `None` if
+    /// the loop wouldn't run the usual way with the current register values.
+    fn apply(self, regs: &mut Regs<i32, 4>) -> Option<usize> {
+        match self {
+            Self::Add { to, from } => {
+                let n = regs.read(Source::Register(from));
+                if n <= 0 {
+                    return None;
+                }
+                *regs.write(to) += n;
+                *regs.write(from) = 0;
+                Some(3)
+            }
+            Self::Multiply {
+                to,
+                from,
+                tmp,
+                counter,
+            } => {
+                let a = regs.read(from);
+                let b = regs.read(Source::Register(counter));
+                if a <= 0 || b <= 0 {
+                    return None;
+                }
+                *regs.write(to) += a * b;
+                *regs.write(tmp) = 0;
+                *regs.write(counter) = 0;
+                Some(6)
+            }
+        }
+    }
+}
+
+fn add_loop(instructions: &[Instruction]) -> Option<(Register, Register)> {
+    use Instruction::*;
+
+    let (to, from, test) = match *instructions {
+        [Inc(Source::Register(to)), Dec(Source::Register(from)), Jnz {
+            test: Source::Register(test),
+            by: Source::Value(-2),
+        }] => (to, from, test),
+        [Dec(Source::Register(from)), Inc(Source::Register(to)), Jnz {
+            test: Source::Register(test),
+            by: Source::Value(-2),
+        }] => (to, from, test),
+        _ => return None,
+    };
+
+    if test == from && to != from {
+        Some((to, from))
+    } else {
+        None
+    }
+}
+
+fn multiply_loop(instructions: &[Instruction]) -> Option<Shortcut> {
+    use Instruction::*;
+
+    match *instructions {
+        [Cpy {
+            from,
+            to: Source::Register(tmp),
+        }, _, _, _, Dec(Source::Register(counter)), Jnz {
+            test: Source::Register(test),
+            by: Source::Value(-5),
+        }] => {
+            let (to, add_from) = add_loop(&instructions[1..4])?;
+            let distinct = add_from == tmp
+                && test == counter
+                && to != counter
+                && tmp != counter
+                && !matches!(from, Source::Register(r) if r == to || r == tmp || r == counter);
+            if distinct {
+                Some(Shortcut::Multiply {
+                    to,
+                    from,
+                    tmp,
+                    counter,
+                })
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn shortcuts(instructions: &[Instruction]) -> Vec<Option<Shortcut>> {
+    (0..instructions.len())
+        .map(|i| {
+            let rest = &instructions[i..];
+            rest.get(..6).and_then(multiply_loop).or_else(|| {
+                rest.get(..3)
+                    .and_then(add_loop)
+                    .map(|(to, from)| Shortcut::Add { to, from })
+            })
+        })
+        .collect()
+}
+
+impl VM {
+    fn step_optimized(&mut self, shortcuts: &mut Vec<Option<Shortcut>>) -> Step<i32> {
+        let shortcut = shortcuts.get(self.ip).copied().flatten();
+        if let Some(len) = shortcut.and_then(|s| s.apply(&mut self.regs)) {
+            self.ip += len;
+            return if self.is_halted() {
+                Step::Halt
+            } else {
+                Step::Continue
+            };
+        }
+
+        let toggles = matches!(self.instructions.get(self.ip), Some(Instruction::Tgl(_)));
+        let step = self.step();
+        if toggles {
+            *shortcuts = self::shortcuts(&self.instructions);
+        }
+        step
+    }
+
+    /// Like `run`, but executes add and multiply loops in a single step. The loops are
+    /// recognised again whenever a `tgl` instruction is executed.
+    pub fn run_optimized(&mut self) {
+        let mut shortcuts = shortcuts(&self.instructions);
+        while self.step_optimized(&mut shortcuts) != Step::Halt {}
+    }
+
+    /// Like `outputs`, but with the loop optimizations of `run_optimized`.
+    pub fn outputs_optimized(&mut self) -> impl Iterator<Item = i32> + '_ {
+        let mut shortcuts = shortcuts(&self.instructions);
+        iter::from_fn(move || loop {
+            match self.step_optimized(&mut shortcuts) {
+                Step::Continue => {}
+                Step::Output(value) => return Some(value),
+                Step::Halt => return None,
+            }
+        })
+    }
+
+    /// Like `run_optimized`, but also returns every instruction that was rewritten by `tgl`,
+    /// in the order in which that happened.
+    pub fn run_with_rewrites(&mut self) -> Vec<Rewrite> {
+        let mut shortcuts = shortcuts(&self.instructions);
+        let mut rewrites = Vec::new();
+
+        loop {
+            let target = match self.instructions.get(self.ip) {
+                Some(&Instruction::Tgl(r)) => {
+                    let at = self.ip as i32 + self.regs.read(Source::Register(r));
+                    Some(at as usize).filter(|&at| at < self.instructions.len())
+                }
+                _ => None,
+            };
+            let ip = self.ip;
+            let before = target.map(|at| self.instructions[at]);
+
+            let step = self.step_optimized(&mut shortcuts);
+
+            if let (Some(at), Some(before)) = (target, before) {
+                rewrites.push(Rewrite {
+                    ip,
+                    at,
+                    before,
+                    after: self.instructions[at],
+                });
+            }
+
+            if step == Step::Halt {
+                return rewrites;
+            }
+        }
+    }
+
+    /// Checks whether the program outputs `0, 1, 0, 1, ...` forever, by running it until the
+    /// state of the machine repeats at the same point in the signal.
+    pub fn is_clock_signal(mut self) -> bool {
+        let mut shortcuts = shortcuts(&self.instructions);
+        let mut seen = HashSet::new();
+        let mut expected = 0;
+
+        loop {
+            match self.step_optimized(&mut shortcuts) {
+                Step::Continue => continue,
+                Step::Output(output) if output == expected => {}
+                _ => return false,
+            }
+            expected = 1 - expected;
+
+            let state = (
+                self.ip,
+                self.regs.clone(),
+                self.instructions.clone(),
+                expected,
+            );
+            if !seen.insert(state) {
+                return true;
+            }
+        }
+    }
+}
+
+/// An instruction at `at` that was toggled by the `tgl` at `ip`.
+#[derive(Debug, Copy, Clone)]
+pub struct Rewrite {
+    pub ip: usize,
+    pub at: usize,
+    pub before: Instruction,
+    pub after: Instruction,
+}
+
+impl Display for Rewrite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>4}: {} -> {} (tgl at {})",
+            self.at, self.before, self.after, self.ip
+        )
+    }
+}
+
+pub fn parser<'a>() -> impl Parser<&'a str, Output = Vec<Instruction>> {
+    let register = register::<4>();
+    let source = source::<i32, 4>();
+
+    let cpy = chain((string("cpy "), source, token(' '), source))
+        .map(|(_, from, _, to)| Instruction::Cpy { from, to });
+    let inc = chain((string("inc "), source)).map(|(_, s)| Instruction::Inc(s));
+    let dec = chain((string("dec "), source)).map(|(_, s)| Instruction::Dec(s));
+    let jnz = chain((string("jnz "), source, token(' '), source))
+        .map(|(_, test, _, by)| Instruction::Jnz { test, by });
+    let tgl = chain((string("tgl "), register)).map(|(_, r)| Instruction::Tgl(r));
+    let out = chain((string("out "), source)).map(|(_, s)| Instruction::Out(s));
+
+    program(choice((cpy, inc, dec, jnz, tgl, out)))
+}
+
+#[test]
+fn test_shortcuts() {
+    use Instruction::*;
+
+    let [a, b, c, d] = [Register(0), Register(1), Register(2), Register(3)];
+    let inc = |r| Inc(Source::Register(r));
+    let dec = |r| Dec(Source::Register(r));
+    let jnz = |test, by| Jnz {
+        test: Source::Register(test),
+        by: Source::Value(by),
+    };
+    let copy = |from, to| Cpy {
+        from: Source::Register(from),
+        to: Source::Register(to),
+    };
+
+    // a += b * d
+    let multiply = [copy(b, c), inc(a), dec(c), jnz(c, -2), dec(d), jnz(d, -5)];
+    let found = shortcuts(&multiply);
+    assert_eq!(
+        found[0],
+        Some(Shortcut::Multiply {
+            to: a,
+            from: Source::Register(b),
+            tmp: c,
+            counter: d,
+        })
+    );
+    assert_eq!(found[1], Some(Shortcut::Add { to: a, from: c }));
+    assert!(found[2..].iter().all(Option::is_none));
+
+    let mut vm = VM::new(multiply.to_vec());
+    vm.regs = Regs([1, 6, 0, 7]);
+    vm.run_optimized();
+    assert_eq!(vm.regs.0, [43, 6, 0, 0]);
+
+    // the loop can't be skipped if it reads the register it writes to
+    let squares = [copy(a, c), inc(a), dec(c), jnz(c, -2), dec(d), jnz(d, -5)];
+    assert_eq!(shortcuts(&squares)[0], None);
+    let add = [dec(b), inc(a), jnz(b, -2)];
+    assert_eq!(shortcuts(&add)[0], Some(Shortcut::Add { to: a, from: b }));
+    assert_eq!(shortcuts(&[inc(a), dec(a), jnz(a, -2)])[0], None);
+
+    // a `tgl` that turns `inc a` into `dec a` breaks both loops
+    let mut program = vec![Tgl(c)];
+    program.extend_from_slice(&multiply);
+    assert!(shortcuts(&program)[1].is_some());
+    let mut vm = VM::new(program.clone());
+    vm.regs = Regs([0, 6, 2, 7]);
+    vm.run_optimized();
+    assert!(shortcuts(&vm.instructions).iter().all(Option::is_none));
+    let mut unoptimized = VM::new(program);
+    unoptimized.regs = Regs([0, 6, 2, 7]);
+    unoptimized.run();
+    assert_eq!(vm.regs, unoptimized.regs);
+    assert_eq!(vm.regs.0, [-42, 6, 0, 0]);
+}
+
+#[test]
+fn test_toggle() {
+    use Instruction::*;
+
+    // toggling `out 5` gives `inc 5`, which is skipped like any other invalid instruction
+    let mut vm = VM::new(vec![
+        Tgl(Register(0)),
+        Out(Source::Value(5)),
+        Inc(Source::Register(Register(0))),
+    ]);
+    vm.regs = Regs([1, 0, 0, 0]);
+    assert_eq!(vm.outputs_optimized().count(), 0);
+    assert_eq!(vm.instructions[1], Inc(Source::Value(5)));
+    assert_eq!(vm.regs.0, [2, 0, 0, 0]);
+}
+
+#[test]
+fn test_clock_signal() {
+    // the shape of the programs of 2016 day 25, which output the bits of `a + 4 * 633`
+    let program = "\
+cpy a d
+cpy 4 c
+cpy 633 b
+inc d
+dec b
+jnz b -2
+dec c
+jnz c -5
+cpy d a
+jnz 0 0
+cpy a b
+cpy 0 a
+cpy 2 c
+jnz b 2
+jnz 1 6
+dec b
+dec c
+jnz c -4
+inc a
+jnz 1 -7
+cpy 2 b
+jnz c 2
+jnz 1 4
+dec b
+dec c
+jnz 1 -4
+jnz 0 0
+out b
+jnz a -19
+jnz 1 -21";
+    let instructions = parser().parse_to_end(program).unwrap();
+    let vm = |a| VM {
+        instructions: instructions.clone(),
+        ip: 0,
+        regs: Regs([a, 0, 0, 0]),
+    };
+
+    // 198 + 2532 = 0b1010_1010_1010
+    assert!(vm(198).is_clock_signal());
+    assert!(!vm(197).is_clock_signal());
+    assert!(!vm(199).is_clock_signal());
+    let expected = [0, 1].iter().copied().cycle().take(24);
+    assert!(vm(198).outputs_optimized().take(24).eq(expected.clone()));
+    assert!(vm(198).outputs().take(24).eq(expected));
+    let bits: Vec<i32> = vm(1).outputs_optimized().take(12).collect();
+    assert_eq!(bits, [1, 0, 1, 0, 0, 1, 1, 1, 1, 0, 0, 1]);
+}
+
+#[test]
+fn test_rewrites() {
+    use Instruction::*;
+
+    // the example of day 23: cpy 2 a, tgl a, tgl a, tgl a, cpy 1 a, dec a, dec a
+    let a = Register(0);
+    let program = vec![
+        Cpy {
+            from: Source::Value(2),
+            to: Source::Register(a),
+        },
+        Tgl(a),
+        Tgl(a),
+        Tgl(a),
+        Cpy {
+            from: Source::Value(1),
+            to: Source::Register(a),
+        },
+        Dec(Source::Register(a)),
+        Dec(Source::Register(a)),
+    ];
+
+    let mut vm = VM::new(program);
+    let rewrites = vm.run_with_rewrites();
+    assert_eq!(vm.regs.0[0], 3);
+    let rewrites: Vec<String> = rewrites.iter().map(|r| r.to_string()).collect();
+    assert_eq!(
+        rewrites,
+        [
+            "   3: tgl a -> inc a (tgl at 1)",
+            "   4: cpy 1 a -> jnz 1 a (tgl at 2)",
+        ]
+    );
+    assert_eq!(vm.instructions[3], Inc(Source::Register(a)));
+    assert_eq!(
+        vm.instructions[4],
+        Jnz {
+            test: Source::Value(1),
+            by: Source::Register(a),
+        }
+    );
+}
+
+#[async_std::test]
+async fn test_display() -> Result<(), crate::input::InputError> {
+    let input = crate::input::get_input(2016, 23).await?;
+    let instructions = parser().parse_to_end(&input).unwrap();
+    assert_eq!(Listing(&instructions).to_string().trim_end(), input);
+    Ok(())
+}
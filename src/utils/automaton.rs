@@ -0,0 +1,474 @@
+use super::{
+    mask::{Mask, Masked},
+    Hex, Pos,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+pub const VON_NEUMANN: [Pos; 4] = [
+    Pos { x: 0, y: -1 },
+    Pos { x: -1, y: 0 },
+    Pos { x: 1, y: 0 },
+    Pos { x: 0, y: 1 },
+];
+
+pub const MOORE: [Pos; 8] = [
+    Pos { x: -1, y: -1 },
+    Pos { x: 0, y: -1 },
+    Pos { x: 1, y: -1 },
+    Pos { x: -1, y: 0 },
+    Pos { x: 1, y: 0 },
+    Pos { x: -1, y: 1 },
+    Pos { x: 0, y: 1 },
+    Pos { x: 1, y: 1 },
+];
+
+/// How neighbors outside of the bounds `min..=max` are treated.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Boundary {
+    Infinite,
+    /// Cells outside of the bounds are always dead.
+    Finite {
+        min: Pos,
+        max: Pos,
+    },
+    /// The grid wraps around at the edges.
+    Toroidal {
+        min: Pos,
+        max: Pos,
+    },
+}
+
+impl Boundary {
+    /// The cell that `pos` refers to, if any.
+    pub fn apply(self, pos: Pos) -> Option<Pos> {
+        match self {
+            Self::Infinite => Some(pos),
+            Self::Finite { min, max } => {
+                if (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y) {
+                    Some(pos)
+                } else {
+                    None
+                }
+            }
+            Self::Toroidal { min, max } => Some(Pos {
+                x: min.x + (pos.x - min.x).rem_euclid(max.x - min.x + 1),
+                y: min.y + (pos.y - min.y).rem_euclid(max.y - min.y + 1),
+            }),
+        }
+    }
+}
+
+pub trait Neighborhood<C> {
+    /// Calls `f` once for every neighbor of `cell`.
+    fn neighbors(&self, cell: C, f: impl FnMut(C));
+}
+
+fn offsets(pos: Pos, offsets: &[Pos], boundary: Boundary, mut f: impl FnMut(Pos)) {
+    for offset in offsets {
+        let neighbor = Pos {
+            x: pos.x + offset.x,
+            y: pos.y + offset.y,
+        };
+        if let Some(neighbor) = boundary.apply(neighbor) {
+            f(neighbor);
+        }
+    }
+}
+
+/// The four orthogonal neighbors.
+#[derive(Copy, Clone, Debug)]
+pub struct VonNeumann(pub Boundary);
+
+impl Neighborhood<Pos> for VonNeumann {
+    fn neighbors(&self, pos: Pos, f: impl FnMut(Pos)) {
+        offsets(pos, &VON_NEUMANN, self.0, f)
+    }
+}
+
+/// The eight orthogonal and diagonal neighbors.
+#[derive(Copy, Clone, Debug)]
+pub struct Moore(pub Boundary);
+
+impl Neighborhood<Pos> for Moore {
+    fn neighbors(&self, pos: Pos, f: impl FnMut(Pos)) {
+        offsets(pos, &MOORE, self.0, f)
+    }
+}
+
+/// The six neighbors on a hexagonal grid.
+#[derive(Copy, Clone, Debug)]
+pub struct Hexagonal;
+
+impl Neighborhood<Hex> for Hexagonal {
+    fn neighbors(&self, hex: Hex, f: impl FnMut(Hex)) {
+        hex.neighbors().for_each(f)
+    }
+}
+
+/// Neighbors given by a function, for cells that aren't positions in a single plane, such as
+/// the levels of a recursive grid.
+#[derive(Copy, Clone, Debug)]
+pub struct Custom<F>(pub F);
+
+impl<C, F, I> Neighborhood<C> for Custom<F>
+where
+    F: Fn(C) -> I,
+    I: IntoIterator<Item = C>,
+{
+    fn neighbors(&self, cell: C, f: impl FnMut(C)) {
+        (self.0)(cell).into_iter().for_each(f)
+    }
+}
+
+/// A rule in the usual `B3/S23` notation: a dead cell becomes alive if its number of live
+/// neighbors is in `birth`, and a live cell stays alive if it's in `survival`.
+pub fn birth_survival(birth: &[usize], survival: &[usize]) -> impl Fn(bool, usize) -> bool {
+    let counts = |counts: &[usize]| {
+        counts.iter().fold(0_u32, |set, &n| {
+            assert!(n < 32, "a rule can't count {} neighbors", n);
+            set | 1 << n
+        })
+    };
+    let (birth, survival) = (counts(birth), counts(survival));
+    move |alive, n| n < 32 && (if alive { survival } else { birth }) >> n & 1 == 1
+}
+
+/// A cellular automaton that only stores its live cells. `rule` gets whether a cell is alive
+/// and its number of live neighbors, and returns whether it's alive in the next generation.
+/// Dead cells without live neighbors stay dead.
+pub struct Automaton<C, N, R> {
+    pub alive: HashSet<C>,
+    neighborhood: N,
+    rule: R,
+}
+
+impl<C, N, R> Automaton<C, N, R>
+where
+    C: Copy + Eq + Hash,
+    N: Neighborhood<C>,
+    R: Fn(bool, usize) -> bool,
+{
+    pub fn new(alive: impl IntoIterator<Item = C>, neighborhood: N, rule: R) -> Self {
+        Self {
+            alive: alive.into_iter().collect(),
+            neighborhood,
+            rule,
+        }
+    }
+
+    pub fn step(&mut self) {
+        let mut counts: HashMap<C, usize> = self.alive.iter().map(|&cell| (cell, 0)).collect();
+        for &cell in &self.alive {
+            self.neighborhood
+                .neighbors(cell, |n| *counts.entry(n).or_insert(0) += 1);
+        }
+
+        let (alive, rule) = (&self.alive, &self.rule);
+        self.alive = counts
+            .into_iter()
+            .filter(|&(cell, n)| rule(alive.contains(&cell), n))
+            .map(|(cell, _)| cell)
+            .collect();
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    pub fn contains(&self, cell: C) -> bool {
+        self.alive.contains(&cell)
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive.len()
+    }
+}
+
+#[derive(Clone, Debug)]
+enum MaskNeighbors<T> {
+    /// The cells that have cell `i` as a neighbor.
+    Table(Vec<Mask<T>>),
+    /// For every neighbor offset, by how far to shift the cells to the right, and which
+    /// cells have a neighbor at that offset.
+    Shifts(Vec<(i32, Mask<T>)>),
+}
+
+/// A cellular automaton whose cells are the bits of a `Mask`, which steps all cells at once
+/// by counting neighbors in binary, one bit plane per bit of the count. There are only four
+/// planes, so no cell can have more than `MAX_NEIGHBORS` neighbors.
+#[derive(Clone, Debug)]
+pub struct MaskAutomaton<T> {
+    neighbors: MaskNeighbors<T>,
+    universe: Mask<T>,
+    birth: u32,
+    survival: u32,
+}
+
+impl<T: Masked> MaskAutomaton<T> {
+    pub const MAX_NEIGHBORS: usize = 15;
+
+    fn with_rule(
+        neighbors: MaskNeighbors<T>,
+        len: u32,
+        rule: impl Fn(bool, usize) -> bool,
+    ) -> Self {
+        let counts = |alive| {
+            (0..=Self::MAX_NEIGHBORS)
+                .filter(|&n| rule(alive, n))
+                .fold(0, |s, n| s | 1 << n)
+        };
+        Self {
+            neighbors,
+            universe: Mask::full(len),
+            birth: counts(false),
+            survival: counts(true),
+        }
+    }
+
+    /// An automaton in which cell `i` influences the cells in `table[i]`, which allows for
+    /// arbitrary layouts such as the levels of a recursive grid. No cell may be influenced by
+    /// more than `MAX_NEIGHBORS` cells.
+    pub fn table(table: Vec<Mask<T>>, rule: impl Fn(bool, usize) -> bool) -> Self {
+        let len = table.len() as u32;
+        Self::with_rule(MaskNeighbors::Table(table), len, rule)
+    }
+
+    /// A finite `width` by `height` grid with neighbors at the given offsets, where the cell
+    /// at `(x, y)` is bit `width * y + x`.
+    pub fn grid(
+        width: u32,
+        height: u32,
+        offsets: &[Pos],
+        rule: impl Fn(bool, usize) -> bool,
+    ) -> Self {
+        assert!(
+            offsets.len() <= Self::MAX_NEIGHBORS,
+            "a cell can't have {} neighbors",
+            offsets.len()
+        );
+        let boundary = Boundary::Finite {
+            min: Pos::origin(),
+            max: Pos {
+                x: width as i32 - 1,
+                y: height as i32 - 1,
+            },
+        };
+        let shifts = offsets
+            .iter()
+            .map(|offset| {
+                let mut keep = Mask::empty();
+                for y in 0..height as i32 {
+                    for x in 0..width as i32 {
+                        let neighbor = Pos {
+                            x: x + offset.x,
+                            y: y + offset.y,
+                        };
+                        if boundary.apply(neighbor).is_some() {
+                            keep.insert(width * y as u32 + x as u32);
+                        }
+                    }
+                }
+                (offset.y * width as i32 + offset.x, keep)
+            })
+            .collect();
+        Self::with_rule(MaskNeighbors::Shifts(shifts), width * height, rule)
+    }
+
+    /// Like `grid`, but for any neighborhood of the positions in the grid, such as a toroidal
+    /// one. Neighbors outside of the grid are ignored.
+    pub fn from_neighborhood(
+        width: u32,
+        height: u32,
+        neighborhood: &impl Neighborhood<Pos>,
+        rule: impl Fn(bool, usize) -> bool,
+    ) -> Self {
+        let contains =
+            |pos: Pos| pos.x >= 0 && pos.y >= 0 && pos.x < width as i32 && pos.y < height as i32;
+        let mut table = vec![Mask::empty(); (width * height) as usize];
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let i = width * y as u32 + x as u32;
+                let mut count = 0;
+                neighborhood.neighbors(Pos { x, y }, |n| {
+                    if contains(n) {
+                        table[(width * n.y as u32 + n.x as u32) as usize].insert(i);
+                        count += 1;
+                    }
+                });
+                assert!(
+                    count <= Self::MAX_NEIGHBORS,
+                    "({}, {}) has {} neighbors",
+                    x,
+                    y,
+                    count
+                );
+            }
+        }
+        Self::table(table, rule)
+    }
+
+    pub fn step(&self, cells: Mask<T>) -> Mask<T> {
+        let mut planes = [Mask::empty(); 4];
+        let mut add = |mut carry: Mask<T>| {
+            for plane in &mut planes {
+                if carry.is_empty() {
+                    break;
+                }
+                let next = *plane & carry;
+                *plane ^= carry;
+                carry = next;
+            }
+        };
+
+        match &self.neighbors {
+            MaskNeighbors::Table(table) => cells.iter().for_each(|i| add(table[i as usize])),
+            MaskNeighbors::Shifts(shifts) => {
+                for &(by, keep) in shifts {
+                    let shifted = if by >= 0 {
+                        cells >> by as u32
+                    } else {
+                        cells << (-by) as u32
+                    };
+                    add(shifted & keep);
+                }
+            }
+        }
+
+        let mut next = Mask::empty();
+        for n in 0..=Self::MAX_NEIGHBORS {
+            let (birth, survival) = (self.birth >> n & 1 == 1, self.survival >> n & 1 == 1);
+            if !birth && !survival {
+                continue;
+            }
+            let count = (0..4).fold(self.universe, |mask, i| {
+                mask & if n >> i & 1 == 1 {
+                    planes[i]
+                } else {
+                    !planes[i]
+                }
+            });
+            match (birth, survival) {
+                (true, true) => next |= count,
+                (true, false) => next |= count & !cells,
+                (false, true) => next |= count & cells,
+                (false, false) => {}
+            }
+        }
+        next
+    }
+}
+
+#[test]
+fn test_automaton() {
+    use super::mask::BitSet;
+
+    let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    let glider = glider.iter().map(|&(x, y)| Pos { x, y });
+    let life = || birth_survival(&[3], &[2, 3]);
+
+    let mut infinite = Automaton::new(glider.clone(), Moore(Boundary::Infinite), life());
+    infinite.run(4);
+    assert_eq!(infinite.len(), 5);
+    assert!(infinite.contains(Pos { x: 2, y: 3 }));
+
+    // a glider on a torus returns to where it started
+    let torus = Boundary::Toroidal {
+        min: Pos::origin(),
+        max: Pos { x: 5, y: 5 },
+    };
+    let mut toroidal = Automaton::new(glider.clone(), Moore(torus), life());
+    toroidal.run(4 * 6);
+    assert_eq!(toroidal.alive, glider.clone().collect());
+
+    // the same on a bit-packed torus
+    let mut cells = Mask::<u64>::empty();
+    glider
+        .clone()
+        .for_each(|p| cells.insert(6 * p.y as u32 + p.x as u32));
+    let automaton = MaskAutomaton::from_neighborhood(6, 6, &Moore(torus), life());
+    let end = (0..4 * 6).fold(cells, |cells, _| automaton.step(cells));
+    assert_eq!(end, cells);
+
+    // a blinker against the edge of a finite grid
+    let mut blinker = Mask::<u32>::empty();
+    (5..8).for_each(|i| blinker.insert(i));
+    let automaton = MaskAutomaton::grid(5, 5, &MOORE, life());
+    let vertical = automaton.step(blinker);
+    assert_eq!(vertical.iter().collect::<Vec<_>>(), vec![1, 6, 11]);
+    assert_eq!(automaton.step(vertical), blinker);
+
+    // a glider crossing the words of a grid that doesn't fit in a primitive mask
+    let cells: Mask<BitSet<3>> = glider
+        .clone()
+        .map(|p| 12 * p.y as u32 + p.x as u32)
+        .collect();
+    let automaton = MaskAutomaton::grid(12, 12, &MOORE, life());
+    let end = (0..16).fold(cells, |cells, _| automaton.step(cells));
+    infinite.run(12);
+    let expected = infinite
+        .alive
+        .iter()
+        .map(|p| 12 * p.y as u32 + p.x as u32)
+        .collect();
+    assert_eq!(end, expected);
+
+    // counts that don't fit in the rule are never in it
+    assert!(!life()(true, 35));
+}
+
+#[test]
+#[should_panic(expected = "(0, 0) has 16 neighbors")]
+fn test_too_many_neighbors() {
+    let everything = Custom(|_| (0..16).map(|i| Pos { x: i % 4, y: i / 4 }));
+    MaskAutomaton::<u32>::from_neighborhood(4, 4, &everything, birth_survival(&[3], &[2, 3]));
+}
+
+#[test]
+fn test_hexagonal() {
+    use super::PointyDir;
+
+    let paths = "\
+        sesenwnenenewseeswwswswwnenewsewsw\n\
+        neeenesenwnwwswnenewnwwsewnenwseswesw\n\
+        seswneswswsenwwnwse\n\
+        nwnwneseeswswnenewneswwnewseswneseene\n\
+        swweswneswnenwsewnwneneseenw\n\
+        eesenwseswswnenwswnwnwsewwnwsene\n\
+        sewnenenenesenwsewnenwwwse\n\
+        wenwwweseeeweswwwnwwe\n\
+        wsweesenenewnwwnwsenewsenwwsesesenwne\n\
+        neeswseenwwswnwswswnw\n\
+        nenwswwsewswnenenewsenwsenwnesesenew\n\
+        enewnwewneswsewnwswenweswnenwsenwsw\n\
+        sweneswneswneneenwnewenewwneswswnese\n\
+        swwesenesewenwneswnwwneseswwne\n\
+        enesenwswwswneneswsenwnewswseenwsese\n\
+        wnwnesenesenenwwnenwsewesewsesesew\n\
+        nenewswnwewswnenesenwnesewesw\n\
+        eneswnwswnwsenenwnwnwwseeswneewsenese\n\
+        neswnwewnwnwseenwseesewsenwsweewe\n\
+        wseweeenwnesenwwwswnew";
+
+    let mut black = HashSet::new();
+    for path in paths.lines() {
+        let path = PointyDir::parse_path(path).unwrap();
+        let tile = path
+            .into_iter()
+            .fold(Hex::origin(), |hex, dir| hex + dir.into());
+        if !black.insert(tile) {
+            black.remove(&tile);
+        }
+    }
+    assert_eq!(black.len(), 10);
+
+    let mut automaton = Automaton::new(black, Hexagonal, |black, n| n == 2 || (black && n == 1));
+    automaton.step();
+    assert_eq!(automaton.len(), 15);
+    automaton.run(9);
+    assert_eq!(automaton.len(), 37);
+}
@@ -1,42 +1,136 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    fmt::Display,
+    fs,
+    hash::{BuildHasher, Hash},
+    io::{self, Write},
+    path::Path,
+    str::FromStr,
+};
 
-pub struct Cache<K, V, F> {
-    cache: HashMap<K, V>,
+/// The number of lookups that a `Cache` could answer without computing anything.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Memoizes a recursive function. Values are cloned out of the cache, so expensive ones such
+/// as strings and vectors are best wrapped in an `Rc`.
+pub struct Cache<K, V, F, S = RandomState> {
+    cache: HashMap<K, V, S>,
     compute: F,
+    stats: CacheStats,
 }
 
 impl<K, V, F> Cache<K, V, F>
 where
     K: Hash + Eq,
-    V: Copy,
+    V: Clone,
     F: Fn(&K, &mut dyn FnMut(K) -> V) -> V,
 {
     pub fn new(compute: F) -> Cache<K, V, F> {
-        let cache = HashMap::new();
-        Cache { cache, compute }
+        Cache::with_hasher(compute, RandomState::new())
+    }
+}
+
+impl<K, V, F, S> Cache<K, V, F, S>
+where
+    K: Hash + Eq,
+    V: Clone,
+    F: Fn(&K, &mut dyn FnMut(K) -> V) -> V,
+    S: BuildHasher,
+{
+    pub fn with_hasher(compute: F, hasher: S) -> Cache<K, V, F, S> {
+        let cache = HashMap::with_hasher(hasher);
+        Cache {
+            cache,
+            compute,
+            stats: CacheStats::default(),
+        }
     }
 
     pub fn get(&mut self, key: K) -> V {
-        get(&mut self.cache, &self.compute, key)
+        get(&mut self.cache, &self.compute, &mut self.stats, key)
     }
 
     pub fn insert(&mut self, key: K, value: V) {
         self.cache.insert(key, value);
     }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// The hits and misses of every lookup so far, including the recursive ones.
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Adds the entries written by `save`, and returns how many there were. A missing file
+    /// counts as an empty one, so that the first run can start from scratch.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> io::Result<usize>
+    where
+        K: FromStr,
+        V: FromStr,
+    {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        let mut count = 0;
+        for (line, n) in contents.lines().zip(1..) {
+            let mut parts = line.splitn(2, '\t');
+            let entry = match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) => key.parse().ok().zip(value.parse().ok()),
+                _ => None,
+            };
+            let (key, value) = entry.ok_or_else(|| {
+                let message = format!("invalid cache entry on line {}", n);
+                io::Error::new(io::ErrorKind::InvalidData, message)
+            })?;
+            self.cache.insert(key, value);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Writes every entry on its own line, with a tab between the key and the value, so
+    /// neither should contain tabs or newlines.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()>
+    where
+        K: Display,
+        V: Display,
+    {
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        for (key, value) in &self.cache {
+            writeln!(file, "{}\t{}", key, value)?;
+        }
+        file.flush()
+    }
 }
 
-fn get<K, V, F>(cache: &mut HashMap<K, V>, compute: &F, key: K) -> V
+fn get<K, V, F, S>(cache: &mut HashMap<K, V, S>, compute: &F, stats: &mut CacheStats, key: K) -> V
 where
     K: Hash + Eq,
-    V: Copy,
+    V: Clone,
     F: Fn(&K, &mut dyn FnMut(K) -> V) -> V,
+    S: BuildHasher,
 {
-    if let Some(&value) = cache.get(&key) {
-        return value;
+    if let Some(value) = cache.get(&key) {
+        stats.hits += 1;
+        return value.clone();
     }
 
-    let value = compute(&key, &mut |k: K| get(cache, compute, k));
-    cache.insert(key, value);
+    stats.misses += 1;
+    let value = compute(&key, &mut |k: K| get(cache, compute, stats, k));
+    cache.insert(key, value.clone());
     value
 }
 
@@ -56,4 +150,49 @@ fn test_fib() {
         fib_memo.get(186),
         332_825_110_087_067_562_321_196_029_789_634_457_848
     );
+    let stats = fib_memo.stats();
+    assert_eq!((stats.hits, stats.misses), (184, 187));
+}
+
+#[test]
+fn test_cache() {
+    use std::rc::Rc;
+
+    // the Collatz sequence from `n` down to 1
+    let mut collatz = Cache::with_hasher(
+        |&n: &u64, collatz: &mut dyn FnMut(u64) -> Rc<Vec<u64>>| {
+            let mut sequence = vec![n];
+            match n {
+                1 => {}
+                _ if n % 2 == 0 => sequence.extend(collatz(n / 2).iter()),
+                _ => sequence.extend(collatz(3 * n + 1).iter()),
+            }
+            Rc::new(sequence)
+        },
+        fxhash::FxBuildHasher::default(),
+    );
+    assert_eq!(*collatz.get(6), [6, 3, 10, 5, 16, 8, 4, 2, 1]);
+    assert_eq!(collatz.get(7).len(), 17);
+    assert_eq!(collatz.stats().hits, 1);
+
+    let name = format!("advent-of-code-test-cache-{}.txt", std::process::id());
+    let path = std::env::temp_dir().join(name);
+    // spreadsheet column names
+    let mut names = Cache::new(|&n: &u32, name: &mut dyn FnMut(u32) -> String| {
+        if n == 0 {
+            return String::new();
+        }
+        let mut s = name((n - 1) / 26);
+        s.push((b'A' + ((n - 1) % 26) as u8) as char);
+        s
+    });
+    assert_eq!(names.get(28), "AB");
+    names.save(&path).unwrap();
+
+    let mut loaded = Cache::new(|_: &u32, _: &mut dyn FnMut(u32) -> String| unreachable!());
+    assert_eq!(loaded.load(&path).unwrap(), 3);
+    assert_eq!(loaded.get(28), "AB");
+    assert_eq!(loaded.get(1), "A");
+    fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.load(&path).unwrap(), 0);
 }
@@ -0,0 +1,196 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hash},
+};
+
+/// Where the sequence of states `x0, step(x0), step(step(x0)), ...` starts repeating: the
+/// state at index `start + len` is the first one that equals an earlier state, namely the
+/// one at index `start`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The earliest index whose state equals the state at index `n`.
+    pub fn reduce(self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+/// Brent's cycle detection, which only keeps two states around. It never returns if the
+/// sequence doesn't repeat.
+pub fn brent<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, len }
+}
+
+/// Floyd's cycle detection, which only keeps two states around. It usually calls `step`
+/// more often than `brent`, and never returns if the sequence doesn't repeat.
+pub fn floyd<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    let mut tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut len = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        len += 1;
+    }
+
+    Cycle { start, len }
+}
+
+/// The number of steps it takes to get back to `initial`, for sequences that are known to
+/// cycle without a lead-in, such as those of reversible systems. Cheaper than `brent`, but
+/// it never returns if `initial` doesn't repeat.
+pub fn period<S: Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> usize {
+    let mut state = step(&initial);
+    let mut len = 1;
+    while state != initial {
+        state = step(&state);
+        len += 1;
+    }
+    len
+}
+
+/// Remembers the index of every state it's given, for sequences that aren't produced by a
+/// single step function, or that are cheaper to hash than to compute twice.
+pub struct CycleDetector<S, H = RandomState> {
+    seen: HashMap<S, usize, H>,
+}
+
+impl<S: Hash + Eq> CycleDetector<S> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<S: Hash + Eq, H: BuildHasher> CycleDetector<S, H> {
+    pub fn with_hasher(hasher: H) -> Self {
+        Self {
+            seen: HashMap::with_hasher(hasher),
+        }
+    }
+
+    /// Records the next state of the sequence, and returns the cycle if it's a repeat.
+    pub fn push(&mut self, state: S) -> Option<Cycle> {
+        let index = self.seen.len();
+        let start = *self.seen.entry(state).or_insert(index);
+        if start == index {
+            None
+        } else {
+            Some(Cycle {
+                start,
+                len: index - start,
+            })
+        }
+    }
+
+    /// The number of distinct states so far.
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+impl<S: Hash + Eq> Default for CycleDetector<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The state at index `n`, where `initial` is at index 0. Once a state repeats, the rest of
+/// the sequence is skipped.
+pub fn nth_state<S>(initial: S, mut step: impl FnMut(&S) -> S, n: usize) -> S
+where
+    S: Clone + Hash + Eq,
+{
+    let mut detector = CycleDetector::new();
+    let mut states = Vec::new();
+    let mut state = initial;
+    while states.len() < n {
+        if let Some(cycle) = detector.push(state.clone()) {
+            return states.swap_remove(cycle.reduce(n));
+        }
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+    state
+}
+
+#[test]
+fn test_cycle() {
+    // 3, 10, 101, 74, 202, 82, 184, 97, 126, 52, 173, 179, 181, 57, 85, 52, ...
+    let step = |&x: &u32| (x * x + 1) % 211;
+    let expected = Cycle { start: 9, len: 6 };
+    assert_eq!(brent(3, step), expected);
+    assert_eq!(floyd(3, step), expected);
+
+    let mut detector = CycleDetector::new();
+    let mut state = 3;
+    let cycle = loop {
+        if let Some(cycle) = detector.push(state) {
+            break cycle;
+        }
+        state = step(&state);
+    };
+    assert_eq!(cycle, expected);
+    assert_eq!(detector.len(), 15);
+
+    let naive = |n| (0..n).fold(3, |x, _| step(&x));
+    for &n in &[0, 1, 9, 14, 15, 16, 1000] {
+        assert_eq!(nth_state(3, step, n), naive(n), "state {}", n);
+    }
+    assert_eq!(expected.reduce(1_000_000_000), 10);
+
+    assert_eq!(brent(0, |&x: &u32| (x + 1) % 5), Cycle { start: 0, len: 5 });
+    assert_eq!(period(0, |&x: &u32| (x + 1) % 5), 5);
+}
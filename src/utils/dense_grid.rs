@@ -0,0 +1,260 @@
+use super::{Grid, Markers, Pos};
+use std::{
+    fmt::{self, Debug, Formatter},
+    ops::{Index, IndexMut},
+};
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct DenseGrid<T> {
+    width: usize,
+    height: usize,
+    elements: Vec<T>,
+}
+
+impl<T: Clone> DenseGrid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self::from_vec(width, height, vec![value; width * height])
+    }
+
+    /// Converts a sparse grid by taking its bounding box, and returns the position in the
+    /// sparse grid of the top left corner.
+    pub fn from_sparse(grid: &Grid<T>) -> (Self, Pos) {
+        let (min, max) = match grid.bounds() {
+            Some(bounds) => bounds,
+            None => return (Self::from_vec(0, 0, Vec::new()), Pos::origin()),
+        };
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        let dense = Self::from_fn(width, height, |pos| {
+            grid[Pos {
+                x: pos.x + min.x,
+                y: pos.y + min.y,
+            }]
+            .clone()
+        });
+        (dense, min)
+    }
+
+    pub fn to_sparse(&self, default: T) -> Grid<T> {
+        let mut grid = Grid::new(default);
+        for (pos, x) in self.iter() {
+            grid[pos] = x.clone();
+        }
+        grid
+    }
+}
+
+impl<T> DenseGrid<T> {
+    pub fn from_vec(width: usize, height: usize, elements: Vec<T>) -> Self {
+        assert_eq!(
+            elements.len(),
+            width * height,
+            "a {}x{} grid needs {} elements",
+            width,
+            height,
+            width * height
+        );
+        Self {
+            width,
+            height,
+            elements,
+        }
+    }
+
+    /// Parses one tile per byte of `input`, which must consist of lines of equal length.
+    /// The positions of the bytes for which `is_marker` returns `true` are collected
+    /// separately.
+    pub fn parse(
+        input: &str,
+        is_marker: impl FnMut(u8) -> bool,
+        mut tile: impl FnMut(u8) -> T,
+    ) -> (Self, Markers) {
+        let width = input.lines().next().map_or(0, str::len);
+        for (i, line) in input.lines().enumerate() {
+            assert!(
+                line.len() == width,
+                "line {} is {} bytes long instead of {}",
+                i + 1,
+                line.len(),
+                width
+            );
+        }
+        let mut elements = Vec::with_capacity(input.len());
+        let markers = Markers::parse(input, is_marker, |_, b| elements.push(tile(b)));
+        let height = elements.len() / width.max(1);
+        (Self::from_vec(width, height, elements), markers)
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let elements = (0..height as i32)
+            .flat_map(|y| (0..width as i32).map(move |x| Pos { x, y }))
+            .map(|pos| f(pos))
+            .collect();
+        Self::from_vec(width, height, elements)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.index_of(pos).is_some()
+    }
+
+    fn index_of(&self, Pos { x, y }: Pos) -> Option<usize> {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some(y as usize * self.width + x as usize)
+        } else {
+            None
+        }
+    }
+
+    fn pos_of(&self, i: usize) -> Pos {
+        Pos {
+            x: (i % self.width) as i32,
+            y: (i / self.width) as i32,
+        }
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        let i = self.index_of(pos)?;
+        Some(&self.elements[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        let i = self.index_of(pos)?;
+        Some(&mut self.elements[i])
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width as i32;
+        (0..self.height as i32).flat_map(move |y| (0..width).map(move |x| Pos { x, y }))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.elements)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Pos, &mut T)> {
+        self.positions().zip(&mut self.elements)
+    }
+
+    pub fn into_iter(self) -> impl Iterator<Item = (Pos, T)> {
+        self.positions().zip(self.elements)
+    }
+
+    /// The in-bounds orthogonal neighbors of `pos`.
+    pub fn neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.neighbors().filter(move |&n| self.contains(n))
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.elements[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.elements[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a chunk size of 0
+        self.elements.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is out of bounds", x);
+        self.elements.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> DenseGrid<U> {
+        DenseGrid {
+            width: self.width,
+            height: self.height,
+            elements: self.elements.into_iter().map(f).collect(),
+        }
+    }
+
+    pub fn position(&self, mut f: impl FnMut(&T) -> bool) -> Option<Pos> {
+        let i = self.elements.iter().position(|x| f(x))?;
+        Some(self.pos_of(i))
+    }
+}
+
+impl<T> Index<Pos> for DenseGrid<T> {
+    type Output = T;
+    fn index(&self, pos: Pos) -> &Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get(pos).unwrap_or_else(|| {
+            panic!("{:?} is out of bounds of a {}x{} grid", pos, width, height)
+        })
+    }
+}
+
+impl<T> IndexMut<Pos> for DenseGrid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(pos).unwrap_or_else(|| {
+            panic!("{:?} is out of bounds of a {}x{} grid", pos, width, height)
+        })
+    }
+}
+
+impl<T: Debug> Debug for DenseGrid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}x{}", self.width, self.height)?;
+
+        for row in self.rows() {
+            for x in row {
+                write!(f, "{:?}", x)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_parse() {
+    let (grid, markers) = DenseGrid::parse("#.a\n@.a", |b| b.is_ascii_lowercase(), |b| b != b'#');
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.row(0), &[false, true, true]);
+    assert_eq!(markers[b'a'], Pos { x: 2, y: 0 });
+    assert_eq!(markers.all(b'a'), &[Pos { x: 2, y: 0 }, Pos { x: 2, y: 1 }]);
+    assert_eq!(markers.get(b'@'), None);
+}
+
+#[test]
+#[should_panic(expected = "line 3 is 2 bytes long instead of 3")]
+fn test_parse_ragged() {
+    DenseGrid::parse("#.#\n...\n#.\n###", |_| false, |b| b);
+}
+
+#[test]
+fn test_dense_grid() {
+    let mut grid = DenseGrid::from_fn(3, 2, |Pos { x, y }| x + 10 * y);
+    grid[Pos { x: 2, y: 1 }] = 99;
+
+    assert_eq!(grid.get(Pos { x: 3, y: 0 }), None);
+    assert_eq!(grid.get(Pos { x: -1, y: 0 }), None);
+    assert_eq!(grid.row(1), &[10, 11, 99]);
+    assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![1, 11]);
+    assert_eq!(grid.position(|&x| x == 11), Some(Pos { x: 1, y: 1 }));
+    assert_eq!(grid.neighbors(Pos::origin()).count(), 2);
+
+    let mut sparse = grid.to_sparse(0);
+    sparse[Pos { x: -1, y: 0 }] = 5;
+    let (dense, offset) = DenseGrid::from_sparse(&sparse);
+    assert_eq!(offset, Pos { x: -1, y: 0 });
+    assert_eq!((dense.width(), dense.height()), (4, 2));
+    assert_eq!(dense.row(0), &[5, 0, 1, 2]);
+    assert_eq!(dense.row(1), &[0, 10, 11, 99]);
+}
@@ -0,0 +1,217 @@
+use super::{DenseGrid, Drawable, Grid, Pos};
+use std::{
+    collections::{HashSet, VecDeque},
+    iter,
+    ops::IndexMut,
+};
+
+/// A breadth-first search from one or more starts, moving orthogonally between the
+/// positions for which `is_open` returns `true`. Yields every reached position together
+/// with its distance to the nearest start, in order of increasing distance.
+pub struct Flood<F> {
+    is_open: F,
+    seen: HashSet<Pos>,
+    queue: VecDeque<(Pos, usize)>,
+}
+
+/// Starts a `Flood`. The starts themselves are yielded first, whether or not they are open.
+pub fn flood<F>(starts: impl IntoIterator<Item = Pos>, is_open: F) -> Flood<F>
+where
+    F: FnMut(Pos) -> bool,
+{
+    let mut seen = HashSet::new();
+    let queue = starts
+        .into_iter()
+        .filter(|&pos| seen.insert(pos))
+        .map(|pos| (pos, 0))
+        .collect();
+    Flood {
+        is_open,
+        seen,
+        queue,
+    }
+}
+
+impl<F> Iterator for Flood<F>
+where
+    F: FnMut(Pos) -> bool,
+{
+    type Item = (Pos, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (pos, distance) = self.queue.pop_front()?;
+        for n in pos.neighbors() {
+            if !self.seen.contains(&n) && (self.is_open)(n) {
+                self.seen.insert(n);
+                self.queue.push_back((n, distance + 1));
+            }
+        }
+        Some((pos, distance))
+    }
+}
+
+/// Breadth-first searches over the tiles of a grid for which `passable` returns `true`.
+/// A sparse `Grid` only consists of the positions that have been written to, which keeps
+/// its searches finite.
+pub trait GridSearch: Drawable {
+    /// A grid with the same positions as `Self`, with an `Option<usize>` per tile.
+    type Overlay: IndexMut<Pos, Output = Option<usize>>;
+
+    /// Whether `pos` is part of the grid and its tile is passable.
+    fn is_open(&self, pos: Pos, passable: impl Fn(&Self::Tile) -> bool) -> bool;
+
+    /// The distance from every reachable tile to the nearest of `starts`.
+    fn distances(
+        &self,
+        starts: impl IntoIterator<Item = Pos>,
+        passable: impl Fn(&Self::Tile) -> bool,
+    ) -> Self::Overlay;
+
+    /// Labels the passable tiles with the index of their connected component, and returns
+    /// the number of components. Components are numbered in the order in which the grid's
+    /// positions are visited.
+    fn components(&self, passable: impl Fn(&Self::Tile) -> bool) -> (Self::Overlay, usize);
+
+    fn reachable(&self, start: Pos, passable: impl Fn(&Self::Tile) -> bool) -> HashSet<Pos> {
+        flood(iter::once(start), |pos| self.is_open(pos, &passable))
+            .map(|(pos, _)| pos)
+            .collect()
+    }
+
+    fn is_reachable(&self, from: Pos, to: Pos, passable: impl Fn(&Self::Tile) -> bool) -> bool {
+        flood(iter::once(from), |pos| self.is_open(pos, &passable)).any(|(pos, _)| pos == to)
+    }
+
+    /// Replaces the tiles reachable from `start` by `value`, and returns how many there
+    /// were.
+    fn flood_fill(
+        &mut self,
+        start: Pos,
+        passable: impl Fn(&Self::Tile) -> bool,
+        value: Self::Tile,
+    ) -> usize
+    where
+        Self: IndexMut<Pos, Output = <Self as Drawable>::Tile>,
+        Self::Tile: Clone,
+    {
+        let reachable = self.reachable(start, passable);
+        for &pos in &reachable {
+            self[pos] = value.clone();
+        }
+        reachable.len()
+    }
+}
+
+fn distances<O>(mut overlay: O, flood: impl Iterator<Item = (Pos, usize)>) -> O
+where
+    O: IndexMut<Pos, Output = Option<usize>>,
+{
+    for (pos, distance) in flood {
+        overlay[pos] = Some(distance);
+    }
+    overlay
+}
+
+fn components<O>(
+    mut overlay: O,
+    positions: impl Iterator<Item = Pos>,
+    is_open: impl Fn(Pos) -> bool,
+) -> (O, usize)
+where
+    O: IndexMut<Pos, Output = Option<usize>>,
+{
+    let mut count = 0;
+    for pos in positions {
+        if overlay[pos].is_none() && is_open(pos) {
+            for (pos, _) in flood(iter::once(pos), &is_open) {
+                overlay[pos] = Some(count);
+            }
+            count += 1;
+        }
+    }
+    (overlay, count)
+}
+
+impl<T: Clone> GridSearch for Grid<T> {
+    type Overlay = Grid<Option<usize>>;
+
+    fn is_open(&self, pos: Pos, passable: impl Fn(&T) -> bool) -> bool {
+        self.contains(pos) && passable(&self[pos])
+    }
+
+    fn distances(
+        &self,
+        starts: impl IntoIterator<Item = Pos>,
+        passable: impl Fn(&T) -> bool,
+    ) -> Self::Overlay {
+        let flood = flood(starts, |pos| self.is_open(pos, &passable));
+        distances(Grid::new(None), flood)
+    }
+
+    fn components(&self, passable: impl Fn(&T) -> bool) -> (Self::Overlay, usize) {
+        let positions = self.iter().map(|(pos, _)| pos);
+        components(Grid::new(None), positions, |pos| {
+            self.is_open(pos, &passable)
+        })
+    }
+}
+
+impl<T> GridSearch for DenseGrid<T> {
+    type Overlay = DenseGrid<Option<usize>>;
+
+    fn is_open(&self, pos: Pos, passable: impl Fn(&T) -> bool) -> bool {
+        self.get(pos).map_or(false, passable)
+    }
+
+    fn distances(
+        &self,
+        starts: impl IntoIterator<Item = Pos>,
+        passable: impl Fn(&T) -> bool,
+    ) -> Self::Overlay {
+        let flood = flood(starts, |pos| self.is_open(pos, &passable));
+        distances(DenseGrid::new(self.width(), self.height(), None), flood)
+    }
+
+    fn components(&self, passable: impl Fn(&T) -> bool) -> (Self::Overlay, usize) {
+        let overlay = DenseGrid::new(self.width(), self.height(), None);
+        components(overlay, self.positions(), |pos| {
+            self.is_open(pos, &passable)
+        })
+    }
+}
+
+#[test]
+fn test_grid_search() {
+    let maze = "\
+        #######\n\
+        #..#..#\n\
+        #.##..#\n\
+        #..#.##\n\
+        #######";
+    let (dense, _) = DenseGrid::parse(maze, |_| false, |b| b);
+    let (sparse, _) = Grid::parse(maze, b'#', |_| false, |b| b);
+    let open = |&b: &u8| b == b'.';
+    let start = Pos { x: 1, y: 1 };
+
+    let distances = dense.distances(iter::once(start), open);
+    assert_eq!(distances[Pos { x: 2, y: 3 }], Some(3));
+    assert_eq!(distances[Pos { x: 4, y: 1 }], None);
+    assert_eq!(
+        sparse.distances(iter::once(start), open)[Pos { x: 2, y: 3 }],
+        Some(3)
+    );
+
+    let (labels, count) = dense.components(open);
+    assert_eq!(count, 2);
+    assert_eq!(labels[Pos { x: 5, y: 2 }], Some(1));
+    assert_eq!(labels[Pos::origin()], None);
+    assert_eq!(sparse.components(open).1, 2);
+
+    assert_eq!(dense.reachable(Pos { x: 4, y: 3 }, open).len(), 5);
+    assert!(sparse.is_reachable(Pos { x: 4, y: 1 }, Pos { x: 4, y: 3 }, open));
+    assert!(!sparse.is_reachable(start, Pos { x: 4, y: 3 }, open));
+
+    let mut dense = dense;
+    assert_eq!(dense.flood_fill(start, open, b'~'), 5);
+    assert_eq!(dense.row(3), b"#~~#.##");
+}
@@ -1,13 +1,10 @@
-use super::Pos;
+use super::{Hex, Pos};
 use std::{
     collections::HashMap,
     fmt::{self, Debug, Formatter},
     ops::{Index, IndexMut},
 };
 
-// TODO:
-// - easily print `Grid<bool>` using `#` and `.` without a custom type
-// - easily print a grid for a given range of coordinates
 #[derive(Clone)]
 pub struct Grid<T> {
     default: T,
@@ -34,6 +31,19 @@ impl<T: Clone> Grid<T> {
         self.elements.into_iter()
     }
 
+    /// Parses one tile per byte of `input`, with `y` increasing downwards. The positions of
+    /// the bytes for which `is_marker` returns `true` are collected separately.
+    pub fn parse(
+        input: &str,
+        default: T,
+        is_marker: impl FnMut(u8) -> bool,
+        mut tile: impl FnMut(u8) -> T,
+    ) -> (Self, Markers) {
+        let mut grid = Self::new(default);
+        let markers = Markers::parse(input, is_marker, |pos, b| grid[pos] = tile(b));
+        (grid, markers)
+    }
+
     pub fn map<U: Clone>(self, mut f: impl FnMut(T) -> U) -> Grid<U> {
         let mut grid = Grid::new(f(self.default));
         for (pos, x) in self.elements {
@@ -43,6 +53,27 @@ impl<T: Clone> Grid<T> {
     }
 }
 
+impl<T> Grid<T> {
+    /// Whether the tile at `pos` has been written to.
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.elements.contains_key(&pos)
+    }
+
+    /// The smallest and largest coordinates of the elements that have been written to.
+    pub fn bounds(&self) -> Option<(Pos, Pos)> {
+        let min_x = self.elements.keys().map(|p| p.x).min()?;
+        let max_x = self.elements.keys().map(|p| p.x).max()?;
+        let min_y = self.elements.keys().map(|p| p.y).min()?;
+        let max_y = self.elements.keys().map(|p| p.y).max()?;
+        Some((Pos { x: min_x, y: min_y }, Pos { x: max_x, y: max_y }))
+    }
+
+    /// The tile at every position that hasn't been written to.
+    pub(super) fn default(&self) -> &T {
+        &self.default
+    }
+}
+
 impl<T> Index<Pos> for Grid<T> {
     type Output = T;
     fn index(&self, pos: Pos) -> &Self::Output {
@@ -57,17 +88,23 @@ impl<T: Clone> IndexMut<Pos> for Grid<T> {
     }
 }
 
+impl<T> Index<Hex> for Grid<T> {
+    type Output = T;
+    fn index(&self, hex: Hex) -> &Self::Output {
+        &self[Pos::from(hex)]
+    }
+}
+
+impl<T: Clone> IndexMut<Hex> for Grid<T> {
+    fn index_mut(&mut self, hex: Hex) -> &mut Self::Output {
+        &mut self[Pos::from(hex)]
+    }
+}
+
 impl<T: Debug> Debug for Grid<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let ranges = try {
-            let min_x = self.elements.keys().map(|p| p.x).min()?;
-            let max_x = self.elements.keys().map(|p| p.x).max()?;
-            let min_y = self.elements.keys().map(|p| p.y).min()?;
-            let max_y = self.elements.keys().map(|p| p.y).max()?;
-            (min_x - 1..=max_x + 1, min_y - 1..=max_y + 1)
-        };
-        let (xs, ys) = match ranges {
-            Some(x) => x,
+        let (xs, ys) = match self.bounds() {
+            Some((min, max)) => (min.x..=max.x, min.y..=max.y),
             None => return write!(f, "an empty grid"),
         };
 
@@ -83,3 +120,55 @@ impl<T: Debug> Debug for Grid<T> {
         Ok(())
     }
 }
+
+/// The positions of the marker bytes found by `Grid::parse` and `DenseGrid::parse`, such as
+/// the `@` entrance of a maze.
+#[derive(Clone, Default, Debug)]
+pub struct Markers(HashMap<u8, Vec<Pos>>);
+
+impl Markers {
+    /// Calls `f` for every byte of `input`, and collects the positions of the markers.
+    pub(super) fn parse(
+        input: &str,
+        mut is_marker: impl FnMut(u8) -> bool,
+        mut f: impl FnMut(Pos, u8),
+    ) -> Self {
+        let mut markers = Self::default();
+        for (line, y) in input.lines().zip(0..) {
+            for (b, x) in line.bytes().zip(0..) {
+                let pos = Pos { x, y };
+                if is_marker(b) {
+                    markers.0.entry(b).or_insert_with(Vec::new).push(pos);
+                }
+                f(pos, b);
+            }
+        }
+        markers
+    }
+
+    /// The first position of `marker` in reading order.
+    pub fn get(&self, marker: u8) -> Option<Pos> {
+        self.all(marker).first().copied()
+    }
+
+    /// All positions of `marker` in reading order.
+    pub fn all(&self, marker: u8) -> &[Pos] {
+        self.0.get(&marker).map_or(&[], |v| v)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, Pos)> + '_ {
+        self.0
+            .iter()
+            .flat_map(|(&b, positions)| positions.iter().map(move |&pos| (b, pos)))
+    }
+}
+
+impl Index<u8> for Markers {
+    type Output = Pos;
+    fn index(&self, marker: u8) -> &Self::Output {
+        match self.all(marker).first() {
+            Some(pos) => pos,
+            None => panic!("marker {:?} not found", marker as char),
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use super::{Drawable, Pos};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+pub type Rgb = [u8; 3];
+
+fn pixels<'a, G, U>(
+    grid: &'a G,
+    (min, max): (Pos, Pos),
+    f: impl Fn(Option<&G::Tile>) -> U + 'a,
+) -> impl Iterator<Item = U> + 'a
+where
+    G: Drawable + ?Sized,
+{
+    (min.y..=max.y)
+        .flat_map(move |y| (min.x..=max.x).map(move |x| Pos { x, y }))
+        .map(move |pos| f(grid.tile(pos)))
+}
+
+fn size((min, max): (Pos, Pos)) -> (usize, usize) {
+    ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize)
+}
+
+/// Encodes the extent of a grid as a binary PBM image, with black pixels where `is_on`
+/// returns `true`.
+pub fn pbm<G>(grid: &G, is_on: impl Fn(&G::Tile) -> bool) -> Vec<u8>
+where
+    G: Drawable + ?Sized,
+{
+    let bounds = grid
+        .extent()
+        .unwrap_or((Pos::origin(), Pos { x: -1, y: -1 }));
+    let (width, height) = size(bounds);
+    let mut image = format!("P4\n{} {}\n", width, height).into_bytes();
+
+    let bits: Vec<bool> = pixels(grid, bounds, |tile| tile.map_or(false, &is_on)).collect();
+    for row in bits.chunks(width.max(1)) {
+        for byte in row.chunks(8) {
+            let byte = byte
+                .iter()
+                .enumerate()
+                .fold(0, |b, (i, &on)| b | (on as u8) << (7 - i));
+            image.push(byte);
+        }
+    }
+    image
+}
+
+/// Encodes the extent of a grid as a binary PPM image.
+pub fn ppm<G>(grid: &G, color: impl Fn(&G::Tile) -> Rgb) -> Vec<u8>
+where
+    G: Drawable + ?Sized,
+{
+    let bounds = grid
+        .extent()
+        .unwrap_or((Pos::origin(), Pos { x: -1, y: -1 }));
+    let (width, height) = size(bounds);
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for rgb in pixels(grid, bounds, |tile| tile.map_or([0; 3], &color)) {
+        image.extend_from_slice(&rgb);
+    }
+    image
+}
+
+/// Writes a numbered PPM image per recorded frame, as `frame_00000.ppm` and onwards.
+pub struct Frames {
+    dir: PathBuf,
+    count: usize,
+}
+
+impl Frames {
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, count: 0 })
+    }
+
+    pub fn record<G>(&mut self, grid: &G, color: impl Fn(&G::Tile) -> Rgb) -> io::Result<()>
+    where
+        G: Drawable + ?Sized,
+    {
+        let path = self.dir.join(format!("frame_{:0>5}.ppm", self.count));
+        self.count += 1;
+        fs::write(path, ppm(grid, color))
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// An animated, endlessly looping GIF of fixed bounds and with a palette of at most 256
+/// colors.
+pub struct Gif {
+    bounds: (Pos, Pos),
+    /// log2 of the size of the color table
+    bits: u8,
+    delay: u16,
+    data: Vec<u8>,
+}
+
+impl Gif {
+    /// Starts an animation of the tiles from `min` to `max` inclusive, showing every frame
+    /// for `delay` hundredths of a second.
+    pub fn new(min: Pos, max: Pos, palette: &[Rgb], delay: u16) -> Self {
+        assert!(!palette.is_empty() && palette.len() <= 256);
+        let (width, height) = size((min, max));
+        let bits = (1..=8).find(|&b| palette.len() <= 1 << b).unwrap();
+
+        let mut data = b"GIF89a".to_vec();
+        data.extend_from_slice(&(width as u16).to_le_bytes());
+        data.extend_from_slice(&(height as u16).to_le_bytes());
+        data.extend_from_slice(&[0xF0 | (bits - 1), 0, 0]);
+        for i in 0..1 << bits {
+            data.extend_from_slice(palette.get(i).unwrap_or(&[0; 3]));
+        }
+        // loop forever
+        data.extend_from_slice(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+        Self {
+            bounds: (min, max),
+            bits,
+            delay,
+            data,
+        }
+    }
+
+    /// Adds a frame, drawing every tile with the color at `index` in the palette. Positions
+    /// without a tile get the first color.
+    pub fn record<G>(&mut self, grid: &G, index: impl Fn(&G::Tile) -> u8)
+    where
+        G: Drawable + ?Sized,
+    {
+        let (width, height) = size(self.bounds);
+        let [delay_lo, delay_hi] = self.delay.to_le_bytes();
+        self.data
+            .extend_from_slice(&[0x21, 0xF9, 0x04, 0, delay_lo, delay_hi, 0, 0]);
+        self.data.extend_from_slice(&[0x2C, 0, 0, 0, 0]);
+        self.data.extend_from_slice(&(width as u16).to_le_bytes());
+        self.data.extend_from_slice(&(height as u16).to_le_bytes());
+        self.data.push(0);
+
+        let min_code_size = self.bits.max(2);
+        let indices = pixels(grid, self.bounds, |tile| tile.map_or(0, &index));
+        let codes = lzw(min_code_size, indices);
+
+        self.data.push(min_code_size);
+        for block in codes.chunks(255) {
+            self.data.push(block.len() as u8);
+            self.data.extend_from_slice(block);
+        }
+        self.data.push(0);
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.data.push(0x3B);
+        self.data
+    }
+
+    pub fn save(self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.finish())
+    }
+}
+
+/// Packs variable-width codes into bytes, least significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    len: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer |= (code as u32) << self.len;
+        self.len += width as u32;
+        while self.len >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn lzw(min_code_size: u8, mut indices: impl Iterator<Item = u8>) -> Vec<u8> {
+    let clear = 1 << min_code_size;
+    let end = clear + 1;
+
+    let mut writer = BitWriter::default();
+    let mut table = HashMap::new();
+    let mut width = min_code_size + 1;
+    let mut next = end + 1;
+    writer.write(clear, width);
+
+    let mut prefix = match indices.next() {
+        Some(index) => index as u16,
+        None => {
+            writer.write(end, width);
+            return writer.finish();
+        }
+    };
+
+    for index in indices {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.write(prefix, width);
+        if next < 4096 {
+            table.insert((prefix, index), next);
+            next += 1;
+            if next > 1 << width && width < 12 {
+                width += 1;
+            }
+        } else {
+            writer.write(clear, width);
+            table.clear();
+            width = min_code_size + 1;
+            next = end + 1;
+        }
+        prefix = index as u16;
+    }
+
+    writer.write(prefix, width);
+    // the decoder adds an entry for the last code before it reads the end code
+    if next == 1 << width && width < 12 {
+        width += 1;
+    }
+    writer.write(end, width);
+    writer.finish()
+}
+
+#[test]
+fn test_images() {
+    use super::DenseGrid;
+
+    let grid = DenseGrid::from_fn(10, 2, |pos| pos.x == pos.y);
+    assert_eq!(pbm(&grid, |&on| on), b"P4\n10 2\n\x80\x00\x40\x00");
+    let image = ppm(&grid, |&on| if on { [255, 0, 0] } else { [0; 3] });
+    let (header, pixels) = image.split_at(12);
+    assert_eq!(header, b"P6\n10 2\n255\n");
+    assert_eq!(pixels.len(), 10 * 2 * 3);
+    assert_eq!(&pixels[..6], &[255, 0, 0, 0, 0, 0]);
+    assert_eq!(&pixels[33..36], &[255, 0, 0]);
+
+    let mut gif = Gif::new(Pos::origin(), Pos { x: 9, y: 1 }, &[[0; 3], [255; 3]], 10);
+    gif.record(&grid, |&on| on as u8);
+    let gif = gif.finish();
+    assert!(gif.starts_with(b"GIF89a\x0A\x00\x02\x00"));
+    assert_eq!(gif.last(), Some(&0x3B));
+}
+
+#[test]
+fn test_lzw() {
+    fn decode(min_code_size: u8, bytes: &[u8]) -> Vec<u8> {
+        let clear = 1 << min_code_size;
+        let end = clear + 1;
+        let mut bits = bytes
+            .iter()
+            .flat_map(|&byte| (0..8).map(move |i| byte >> i & 1));
+        let mut read = |width: u8| {
+            (0..width).try_fold(0, |code, i| bits.next().map(|bit| code | (bit as u16) << i))
+        };
+
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut width = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut output = Vec::new();
+        loop {
+            let code = read(width).expect("missing end code");
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.resize(clear as usize + 2, Vec::new());
+                width = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == end {
+                return output;
+            }
+
+            let entry = match (table.get(code as usize), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) if code as usize == table.len() => {
+                    let mut entry = previous.clone();
+                    entry.push(previous[0]);
+                    entry
+                }
+                _ => panic!("invalid code {}", code),
+            };
+            if let Some(mut previous) = previous {
+                previous.push(entry[0]);
+                if table.len() < 4096 {
+                    table.push(previous);
+                }
+                if table.len() == 1 << width && width < 12 {
+                    width += 1;
+                }
+            }
+            output.extend(&entry);
+            previous = Some(entry);
+        }
+    }
+
+    let round_trip = |min_code_size: u8, indices: &[u8]| {
+        let bytes = lzw(min_code_size, indices.iter().copied());
+        assert_eq!(decode(min_code_size, &bytes), indices);
+    };
+
+    round_trip(2, &[]);
+    round_trip(2, &[3]);
+    round_trip(2, &[0; 100]);
+    // the end code is the first one of 4 bits, right after 48 bits of codes
+    let cycle: Vec<u8> = (0..19).map(|i| i % 4).collect();
+    round_trip(2, &cycle);
+
+    // enough codes to fill up the table a few times
+    let mut x = 1u32;
+    let noise: Vec<u8> = (0..50_000)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 17;
+            x ^= x << 5;
+            x as u8
+        })
+        .collect();
+    round_trip(8, &noise);
+    let bits: Vec<u8> = noise.iter().map(|&x| x % 4).collect();
+    round_trip(2, &bits);
+}
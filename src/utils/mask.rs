@@ -1,5 +1,8 @@
-use num::traits::{One, Zero};
-use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not, Shl, Shr};
+use std::{
+    fmt::{self, Display, Formatter},
+    iter::{self, FromIterator},
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, Shr},
+};
 
 pub trait Masked:
     Sized
@@ -7,38 +10,56 @@ pub trait Masked:
     + BitAndAssign
     + BitOr<Output = Self>
     + BitOrAssign
+    + BitXor<Output = Self>
     + Not<Output = Self>
     + Shl<u32, Output = Self>
     + Shr<u32, Output = Self>
     + PartialEq
-    + One
-    + Zero
     + Copy
 {
+    const BITS: u32;
+    const ZERO: Self;
+
+    /// The value with only bit `i` set.
+    fn bit(i: u32) -> Self;
     fn trailing_zeros(self) -> u32;
     fn count_ones(self) -> u32;
+
+    /// The smallest subset of `mask` that's larger than `self`, which has to be a subset of
+    /// `mask` as well, or zero if there is none.
+    fn next_subset_of(self, mask: Self) -> Self;
 }
 
-impl Masked for u32 {
-    fn trailing_zeros(self) -> u32 {
-        self.trailing_zeros()
-    }
+macro_rules! impl_masked {
+    ($($t:ty: $bits:expr),*) => {
+        $(
+            impl Masked for $t {
+                const BITS: u32 = $bits;
+                const ZERO: Self = 0;
 
-    fn count_ones(self) -> u32 {
-        self.count_ones()
-    }
-}
+                fn bit(i: u32) -> Self {
+                    1 << i
+                }
 
-impl Masked for u64 {
-    fn trailing_zeros(self) -> u32 {
-        self.trailing_zeros()
-    }
+                fn trailing_zeros(self) -> u32 {
+                    self.trailing_zeros()
+                }
 
-    fn count_ones(self) -> u32 {
-        self.count_ones()
-    }
+                fn count_ones(self) -> u32 {
+                    self.count_ones()
+                }
+
+                fn next_subset_of(self, mask: Self) -> Self {
+                    self.wrapping_sub(mask) & mask
+                }
+            }
+        )*
+    };
 }
 
+// `u128` holds a row of 100 tiles in 2016 day 18
+impl_masked!(u32: 32, u64: 64, u128: 128);
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Mask<T>(pub T);
 
@@ -47,15 +68,25 @@ where
     T: Masked,
 {
     pub fn empty() -> Self {
-        Self(T::zero())
+        Self(T::ZERO)
+    }
+
+    /// The mask of bits `0..len`.
+    pub fn full(len: u32) -> Self {
+        assert!(len <= T::BITS, "a mask can't have {} bits", len);
+        if len == 0 {
+            Self::empty()
+        } else {
+            Self(!T::ZERO >> (T::BITS - len))
+        }
     }
 
     pub fn insert(&mut self, i: u32) {
-        self.0 |= T::one() << i;
+        self.0 |= T::bit(i);
     }
 
     pub fn remove(&mut self, i: u32) {
-        self.0 &= !(T::one() << i);
+        self.0 &= !T::bit(i);
     }
 
     pub fn add(&mut self, other: Self) {
@@ -63,11 +94,24 @@ where
     }
 
     pub fn contains(self, i: u32) -> bool {
-        (self.0 >> i) & T::one() == T::one()
+        self.0 & T::bit(i) != T::ZERO
     }
 
     pub fn intersects(self, other: Self) -> bool {
-        (self.0 & other.0) != T::zero()
+        (self.0 & other.0) != T::ZERO
+    }
+
+    /// The bits of `self` that aren't in `other`.
+    pub fn difference(self, other: Self) -> Self {
+        self & !other
+    }
+
+    pub fn is_subset(self, other: Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn is_superset(self, other: Self) -> bool {
+        other.is_subset(self)
     }
 
     pub fn is_empty(self) -> bool {
@@ -79,7 +123,7 @@ where
     }
 
     pub fn iter(mut self) -> impl Iterator<Item = u32> {
-        std::iter::from_fn(move || {
+        iter::from_fn(move || {
             if self.is_empty() {
                 None
             } else {
@@ -89,6 +133,66 @@ where
             }
         })
     }
+
+    /// Every subset of the mask in increasing order, starting with the empty one.
+    pub fn submasks(self) -> impl Iterator<Item = Self> {
+        let mut next = Some(Self::empty());
+        iter::from_fn(move || {
+            let current = next?;
+            next = Some(Self(current.0.next_subset_of(self.0))).filter(|sub| !sub.is_empty());
+            Some(current)
+        })
+    }
+
+    /// Every superset of the mask within `universe`, starting with the mask itself.
+    pub fn supersets(self, universe: Self) -> impl Iterator<Item = Self> {
+        universe
+            .difference(self)
+            .submasks()
+            .map(move |extra| self | extra)
+    }
+
+    /// Every subset of the mask with `k` bits, in lexicographic order of their bits.
+    pub fn combinations(self, k: u32) -> impl Iterator<Item = Self> {
+        let bits: Vec<u32> = self.iter().collect();
+        let (n, k) = (bits.len(), k as usize);
+        let mut indices: Option<Vec<usize>> = if k <= n { Some((0..k).collect()) } else { None };
+        iter::from_fn(move || {
+            let current = indices.as_mut()?;
+            let mask = current.iter().map(|&i| bits[i]).collect();
+            match (0..k).rev().find(|&i| current[i] != n - k + i) {
+                Some(i) => {
+                    current[i] += 1;
+                    for j in i + 1..k {
+                        current[j] = current[j - 1] + 1;
+                    }
+                }
+                None => indices = None,
+            }
+            Some(mask)
+        })
+    }
+}
+
+impl<T: Masked> FromIterator<u32> for Mask<T> {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut mask = Self::empty();
+        iter.into_iter().for_each(|i| mask.insert(i));
+        mask
+    }
+}
+
+impl<T: Masked> Display for Mask<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (n, i) in self.iter().enumerate() {
+            if n > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", i)?;
+        }
+        write!(f, "}}")
+    }
 }
 
 impl<T> BitAnd for Mask<T>
@@ -131,6 +235,26 @@ where
     }
 }
 
+impl<T> BitXor for Mask<T>
+where
+    T: Masked,
+{
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self::Output {
+        Self(self.0 ^ other.0)
+    }
+}
+
+impl<T> BitXorAssign for Mask<T>
+where
+    T: Masked,
+{
+    fn bitxor_assign(&mut self, other: Self) {
+        self.0 = self.0 ^ other.0;
+    }
+}
+
 impl<T> Not for Mask<T>
 where
     T: Masked,
@@ -169,3 +293,186 @@ where
         Self(self.0 >> n)
     }
 }
+
+/// A fixed number of bits, `64 * N`, for masks that don't fit in a primitive integer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitSet<const N: usize>(pub [u64; N]);
+
+impl<const N: usize> Masked for BitSet<N> {
+    const BITS: u32 = 64 * N as u32;
+    const ZERO: Self = Self([0; N]);
+
+    fn bit(i: u32) -> Self {
+        let mut words = [0; N];
+        words[i as usize / 64] = 1 << (i % 64);
+        Self(words)
+    }
+
+    fn trailing_zeros(self) -> u32 {
+        match self.0.iter().position(|&word| word != 0) {
+            Some(i) => 64 * i as u32 + self.0[i].trailing_zeros(),
+            None => Self::BITS,
+        }
+    }
+
+    fn count_ones(self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    fn next_subset_of(self, mask: Self) -> Self {
+        // adds 1 to `self | !mask`, so that the carry skips over the bits outside of `mask`
+        let mut next = self | !mask;
+        for word in &mut next.0 {
+            let (sum, carry) = word.overflowing_add(1);
+            *word = sum;
+            if !carry {
+                break;
+            }
+        }
+        next & mask
+    }
+}
+
+macro_rules! impl_bitset_op {
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident, $token:tt) => {
+        impl<const N: usize> $op for BitSet<N> {
+            type Output = Self;
+
+            fn $f(mut self, other: Self) -> Self::Output {
+                self $token other;
+                self
+            }
+        }
+
+        impl<const N: usize> $op_assign for BitSet<N> {
+            fn $f_assign(&mut self, other: Self) {
+                for (a, b) in self.0.iter_mut().zip(&other.0) {
+                    *a $token b;
+                }
+            }
+        }
+    };
+}
+
+impl_bitset_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+impl_bitset_op!(BitOr, bitor, BitOrAssign, bitor_assign, |=);
+impl_bitset_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
+
+impl<const N: usize> Not for BitSet<N> {
+    type Output = Self;
+
+    fn not(mut self) -> Self::Output {
+        self.0.iter_mut().for_each(|word| *word = !*word);
+        self
+    }
+}
+
+impl<const N: usize> Shl<u32> for BitSet<N> {
+    type Output = Self;
+
+    fn shl(self, n: u32) -> Self::Output {
+        let (words, bits) = (n as usize / 64, n % 64);
+        let mut result = [0; N];
+        for (i, word) in result.iter_mut().enumerate().skip(words) {
+            *word = self.0[i - words] << bits;
+            if bits > 0 && i > words {
+                *word |= self.0[i - words - 1] >> (64 - bits);
+            }
+        }
+        Self(result)
+    }
+}
+
+impl<const N: usize> Shr<u32> for BitSet<N> {
+    type Output = Self;
+
+    fn shr(self, n: u32) -> Self::Output {
+        let (words, bits) = (n as usize / 64, n % 64);
+        let mut result = [0; N];
+        for (i, word) in result.iter_mut().enumerate().take(N.saturating_sub(words)) {
+            *word = self.0[i + words] >> bits;
+            if bits > 0 && i + words + 1 < N {
+                *word |= self.0[i + words + 1] << (64 - bits);
+            }
+        }
+        Self(result)
+    }
+}
+
+#[test]
+fn test_mask() {
+    let mask: Mask<u32> = [1, 4, 6].iter().copied().collect();
+    assert_eq!(mask, Mask(0b101_0010));
+    assert_eq!(mask.to_string(), "{1, 4, 6}");
+    assert_eq!(Mask::<u32>::empty().to_string(), "{}");
+    assert_eq!(Mask::<u32>::full(4), Mask(0b1111));
+    assert_eq!(Mask::<u32>::full(32), Mask(u32::MAX));
+
+    let other = Mask(0b11_0000);
+    assert_eq!(mask.difference(other), Mask(0b100_0010));
+    assert!(Mask(0b100_0010).is_subset(mask));
+    assert!(!mask.is_subset(other));
+    assert!(mask.is_superset(Mask::empty()));
+
+    let submasks: Vec<u32> = Mask(0b1010u32).submasks().map(|m| m.0).collect();
+    assert_eq!(submasks, [0b0000, 0b0010, 0b1000, 0b1010]);
+    assert_eq!(mask.submasks().count(), 8);
+    assert!(mask.submasks().all(|m| m.is_subset(mask)));
+
+    let supersets: Vec<u32> = Mask(0b0100u32)
+        .supersets(Mask(0b1101))
+        .map(|m| m.0)
+        .collect();
+    assert_eq!(supersets, [0b0100, 0b0101, 0b1100, 0b1101]);
+
+    let pairs: Vec<String> = mask.combinations(2).map(|m| m.to_string()).collect();
+    assert_eq!(pairs, ["{1, 4}", "{1, 6}", "{4, 6}"]);
+    assert_eq!(Mask::<u64>::full(10).combinations(4).count(), 210);
+    assert_eq!(mask.combinations(0).collect::<Vec<_>>(), [Mask::empty()]);
+    assert_eq!(mask.combinations(4).count(), 0);
+
+    let wide: Mask<u128> = [3, 100, 127].iter().copied().collect();
+    assert_eq!(wide.iter().collect::<Vec<_>>(), [3, 100, 127]);
+}
+
+#[test]
+fn test_bitset() {
+    let bits = [0, 63, 64, 100, 191];
+    let mask: Mask<BitSet<3>> = bits.iter().copied().collect();
+    assert_eq!(mask.len(), 5);
+    assert_eq!(mask.iter().collect::<Vec<_>>(), bits);
+    assert!(mask.contains(64) && !mask.contains(65));
+    assert_eq!(mask.to_string(), "{0, 63, 64, 100, 191}");
+
+    let shifted = (mask << 1).iter().collect::<Vec<_>>();
+    assert_eq!(shifted, [1, 64, 65, 101]);
+    let shifted = (mask >> 64).iter().collect::<Vec<_>>();
+    assert_eq!(shifted, [0, 36, 127]);
+    assert_eq!((mask >> 200), Mask::empty());
+    assert_eq!(Mask::<BitSet<3>>::full(130).len(), 130);
+    assert_eq!((!mask).len(), 192 - 5);
+
+    // agrees with the primitive masks
+    let narrow: Mask<u128> = bits[..4].iter().copied().collect();
+    let wide: Mask<BitSet<2>> = narrow.iter().collect();
+    for n in &[0, 1, 37, 64, 65, 127] {
+        assert_eq!(
+            (wide << *n).iter().collect::<Vec<_>>(),
+            (narrow << *n).iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            (wide >> *n).iter().collect::<Vec<_>>(),
+            (narrow >> *n).iter().collect::<Vec<_>>()
+        );
+    }
+    // the carry from bit 63 to bit 64 crosses a word
+    let submasks = |m: Mask<u128>| m.iter().collect::<Vec<_>>();
+    assert!(wide
+        .submasks()
+        .map(|m| m.iter().collect::<Vec<_>>())
+        .eq(narrow.submasks().map(submasks)));
+    // the walk is lazy, so a huge universe is fine
+    let mut supersets = wide.supersets(Mask::full(128));
+    assert_eq!(supersets.next(), Some(wide));
+    assert_eq!(supersets.nth(1), Some(wide | Mask(BitSet::bit(2))));
+}
@@ -0,0 +1,128 @@
+use super::{
+    mask::{Mask, Masked},
+    GridSearch, Pos,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// A shortest path between two points of interest of a maze.
+#[derive(Copy, Clone, Debug)]
+pub struct Edge<T> {
+    pub to: usize,
+    pub distance: usize,
+    /// The annotated tiles along the path, not counting its endpoints.
+    pub crossed: Mask<T>,
+}
+
+/// A maze reduced to its points of interest and the shortest paths between them, with the
+/// points numbered in the order in which they were given.
+pub struct MazeGraph<T> {
+    points: Vec<Pos>,
+    edges: Vec<Vec<Edge<T>>>,
+}
+
+impl<T: Masked> MazeGraph<T> {
+    /// Finds the shortest path from every point to every other point that it can reach over
+    /// passable tiles. `annotate` can assign a bit to a tile, such as a door, which is then
+    /// included in the mask of every path that crosses it.
+    pub fn new<G: GridSearch>(
+        grid: &G,
+        points: &[Pos],
+        passable: impl Fn(&G::Tile) -> bool,
+        annotate: impl Fn(&G::Tile) -> Option<u32>,
+    ) -> Self {
+        let indices: HashMap<Pos, usize> = points.iter().zip(0..).map(|(&p, i)| (p, i)).collect();
+
+        let edges = points
+            .iter()
+            .map(|&start| {
+                let mut edges = Vec::new();
+                let mut seen = HashSet::new();
+                let mut queue = VecDeque::new();
+                seen.insert(start);
+                queue.push_back((start, 0, Mask::empty()));
+
+                while let Some((pos, distance, mut crossed)) = queue.pop_front() {
+                    if pos != start {
+                        if let Some(&to) = indices.get(&pos) {
+                            edges.push(Edge {
+                                to,
+                                distance,
+                                crossed,
+                            });
+                        }
+                        if let Some(bit) = grid.tile(pos).and_then(&annotate) {
+                            crossed.insert(bit);
+                        }
+                    }
+
+                    for n in pos.neighbors() {
+                        if grid.is_open(n, &passable) && seen.insert(n) {
+                            queue.push_back((n, distance + 1, crossed));
+                        }
+                    }
+                }
+
+                edges
+            })
+            .collect();
+
+        Self {
+            points: points.to_vec(),
+            edges,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn point(&self, i: usize) -> Pos {
+        self.points[i]
+    }
+
+    /// The paths from point `i` to the other points that it can reach, nearest first.
+    pub fn edges(&self, i: usize) -> &[Edge<T>] {
+        &self.edges[i]
+    }
+
+    pub fn edge(&self, from: usize, to: usize) -> Option<&Edge<T>> {
+        self.edges[from].iter().find(|edge| edge.to == to)
+    }
+
+    pub fn distance(&self, from: usize, to: usize) -> Option<usize> {
+        Some(self.edge(from, to)?.distance)
+    }
+}
+
+#[test]
+fn test_maze_graph() {
+    use super::DenseGrid;
+
+    let maze = "\
+        #########\n\
+        #a.A.@.b#\n\
+        ###.#####\n\
+        #c.B#..d#\n\
+        #########";
+    let (grid, markers) = DenseGrid::parse(maze, |b| b != b'#' && b != b'.', |b| b);
+    let points: Vec<Pos> = b"@abcd".iter().map(|&b| markers[b]).collect();
+    let graph = MazeGraph::<u32>::new(
+        &grid,
+        &points,
+        |&b| b != b'#',
+        |&b| match b {
+            b'A'..=b'Z' => Some((b - b'A') as u32),
+            _ => None,
+        },
+    );
+
+    assert_eq!(graph.len(), 5);
+    assert_eq!(graph.distance(0, 1), Some(4));
+    assert_eq!(graph.distance(0, 4), None);
+    assert_eq!(graph.edge(1, 2).unwrap().distance, 6);
+    assert_eq!(graph.edge(1, 2).unwrap().crossed, Mask(0b01));
+    assert_eq!(graph.edge(2, 3).unwrap().crossed, Mask(0b11));
+    assert!(graph.edge(0, 2).unwrap().crossed.is_empty());
+    let nearest: Vec<usize> = graph.edges(0).iter().map(|edge| edge.to).collect();
+    assert_eq!(nearest, [2, 1, 3]);
+}
@@ -1,19 +1,43 @@
+pub mod automaton;
 pub mod cache;
+pub mod cycle;
+pub mod dense_grid;
 pub mod digits;
+pub mod flood;
 pub mod grid;
+pub mod image;
 pub mod mask;
+pub mod maze;
+pub mod ocr;
 pub mod pos;
+pub mod pos_n;
+pub mod register_machine;
+pub mod render;
 pub mod search;
+pub mod transform;
+pub mod tsp;
 pub mod useful_parsers;
 
+pub mod arcade;
+pub mod assembunny;
 pub mod fast_intcode;
 pub mod intcode;
 
+pub use automaton::*;
 pub use cache::*;
+pub use cycle::*;
+pub use dense_grid::*;
 pub use digits::*;
+pub use flood::*;
 pub use grid::*;
+pub use maze::*;
+pub use ocr::*;
 pub use pos::*;
+pub use pos_n::*;
+pub use render::*;
 pub use search::*;
+pub use transform::*;
+pub use tsp::*;
 pub use useful_parsers::*;
 
 use std::{collections::HashMap, hash::Hash};
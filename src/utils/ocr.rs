@@ -0,0 +1,147 @@
+use super::{Drawable, Pos};
+use once_cell::sync::Lazy;
+
+const SMALL_LETTERS: &str = "ABCEFGHIJKLOPRSUYZ";
+const SMALL_FONT: [&str; 6] = [
+    ".##..###...##..####.####..##..#..#.###...##.#..#.#.....##..###..###...###.#..#.#...#.####",
+    "#..#.#..#.#..#.#....#....#..#.#..#..#.....#.#.#..#....#..#.#..#.#..#.#....#..#.#...#....#",
+    "#..#.###..#....###..###..#....####..#.....#.##...#....#..#.#..#.#..#.#....#..#..#.#....#.",
+    "####.#..#.#....#....#....#.##.#..#..#.....#.#.#..#....#..#.###..###...##..#..#...#....#..",
+    "#..#.#..#.#..#.#....#....#..#.#..#..#..#..#.#.#..#....#..#.#....#.#.....#.#..#...#...#...",
+    "#..#.###...##..####.#.....###.#..#.###..##..#..#.####..##..#....#..#.###...##....#...####",
+];
+
+const LARGE_LETTERS: &str = "ABCEFGHJKLNPRXZ";
+const LARGE_FONT: [&str; 10] = [
+    "..##...#####...####..######.######..####..#....#....###.#....#.#......#....#.#####..#####..#....#.######",
+    ".#..#..#....#.#....#.#......#......#....#.#....#.....#..#...#..#......##...#.#....#.#....#.#....#......#",
+    "#....#.#....#.#......#......#......#......#....#.....#..#..#...#......##...#.#....#.#....#..#..#.......#",
+    "#....#.#....#.#......#......#......#......#....#.....#..#.#....#......#.#..#.#....#.#....#..#..#......#.",
+    "#....#.#####..#......#####..#####..#......######.....#..##.....#......#.#..#.#####..#####....##......#..",
+    "######.#....#.#......#......#......#..###.#....#.....#..##.....#......#..#.#.#......#..#.....##.....#...",
+    "#....#.#....#.#......#......#......#....#.#....#.....#..#.#....#......#..#.#.#......#...#...#..#...#....",
+    "#....#.#....#.#......#......#......#....#.#....#.#...#..#..#...#......#...##.#......#...#...#..#..#.....",
+    "#....#.#....#.#....#.#......#......#...##.#....#.#...#..#...#..#......#...##.#......#....#.#....#.#.....",
+    "#....#.#####...####..######.#.......###.#.#....#..###...#....#.######.#....#.#......#....#.#....#.######",
+];
+
+/// The columns of a letter, with the top row as the least significant bit.
+type Glyph = Vec<u32>;
+
+static SMALL: Lazy<Vec<(Glyph, char)>> = Lazy::new(|| font(&SMALL_FONT, SMALL_LETTERS));
+static LARGE: Lazy<Vec<(Glyph, char)>> = Lazy::new(|| font(&LARGE_FONT, LARGE_LETTERS));
+
+fn font(rows: &[&str], letters: &str) -> Vec<(Glyph, char)> {
+    let raster: Vec<Vec<bool>> = rows
+        .iter()
+        .map(|row| row.bytes().map(|b| b == b'#').collect())
+        .collect();
+    let (columns, _) = columns(&raster);
+    let glyphs: Vec<Glyph> = columns
+        .split(|&c| c == 0)
+        .filter(|g| !g.is_empty())
+        .map(|g| g.to_vec())
+        .collect();
+    assert_eq!(glyphs.len(), letters.len());
+    glyphs.into_iter().zip(letters.chars()).collect()
+}
+
+/// Converts a raster to column bitmasks, dropping the empty rows above and below, and
+/// returns them along with the number of remaining rows.
+fn columns(raster: &[Vec<bool>]) -> (Vec<u32>, usize) {
+    let width = raster.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize, y: usize| raster[y].get(x).copied().unwrap_or(false);
+    let rows: Vec<usize> = (0..raster.len())
+        .filter(|&y| (0..width).any(|x| lit(x, y)))
+        .collect();
+    let (top, bottom) = match (rows.first(), rows.last()) {
+        (Some(&top), Some(&bottom)) => (top, bottom),
+        _ => return (Vec::new(), 0),
+    };
+
+    let columns = (0..width)
+        .map(|x| {
+            (top..=bottom)
+                .filter(|&y| lit(x, y))
+                .fold(0, |mask, y| mask | 1 << (y - top))
+        })
+        .collect();
+    (columns, bottom - top + 1)
+}
+
+/// Matches the longest letter at every non-empty column, because some letters such as `Y`
+/// aren't followed by an empty column.
+fn recognize(raster: &[Vec<bool>]) -> Option<String> {
+    let (columns, height) = columns(raster);
+    let font = match height {
+        6 => &SMALL,
+        10 => &LARGE,
+        _ => return None,
+    };
+
+    let mut letters = String::new();
+    let mut x = 0;
+
+    while x < columns.len() {
+        if columns[x] == 0 {
+            x += 1;
+            continue;
+        }
+        let (glyph, letter) = font
+            .iter()
+            .filter(|(g, _)| columns[x..].starts_with(g))
+            .max_by_key(|(g, _)| g.len())?;
+        letters.push(*letter);
+        x += glyph.len();
+    }
+
+    Some(letters)
+}
+
+/// Reads the letters drawn by the lit tiles of a grid, in either the 4x6 or the 6x10 font.
+pub fn ocr<G>(grid: &G, is_lit: impl Fn(&G::Tile) -> bool) -> Option<String>
+where
+    G: Drawable + ?Sized,
+{
+    let (min, max) = grid.extent()?;
+    let raster: Vec<Vec<bool>> = (min.y..=max.y)
+        .map(|y| {
+            (min.x..=max.x)
+                .map(|x| grid.tile(Pos { x, y }).map_or(false, &is_lit))
+                .collect()
+        })
+        .collect();
+    recognize(&raster)
+}
+
+/// Like `ocr`, but for letters drawn as text, where spaces and `.` are unlit.
+pub fn ocr_str(s: &str) -> Option<String> {
+    let raster: Vec<Vec<bool>> = s
+        .lines()
+        .map(|line| line.chars().map(|c| c != ' ' && c != '.').collect())
+        .collect();
+    recognize(&raster)
+}
+
+#[test]
+fn test_ocr() {
+    for (rows, letters) in [
+        (&SMALL_FONT[..], SMALL_LETTERS),
+        (&LARGE_FONT[..], LARGE_LETTERS),
+    ]
+    .iter()
+    {
+        assert_eq!(ocr_str(&rows.join("\n")).as_deref(), Some(*letters));
+    }
+
+    assert_eq!(
+        ocr_str(" ##  \n#  # \n#    \n#    \n#  # \n ##  "),
+        Some("C".into())
+    );
+    // `Y` touching the next letter
+    assert_eq!(
+        ocr_str("#...#####\n#...##...\n.#.#.###.\n..#..#...\n..#..#...\n..#..#..."),
+        Some("YF".into())
+    );
+    assert_eq!(ocr_str("####\n#  #\n#  #\n#  #\n#  #\n####"), None);
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Pos {
@@ -16,12 +17,7 @@ impl Pos {
     }
 
     pub fn move_to(&mut self, dir: Dir) {
-        match dir {
-            Dir::North => self.y -= 1,
-            Dir::South => self.y += 1,
-            Dir::West => self.x -= 1,
-            Dir::East => self.x += 1,
-        }
+        *self += dir.into();
     }
 
     pub fn moving_to(mut self, dir: Dir) -> Self {
@@ -42,21 +38,93 @@ impl Pos {
     }
 
     pub fn diag_neighbors(self) -> impl Iterator<Item = Self> {
-        iter!([
-            self.moving_to(Dir::West),
-            self.moving_to(Dir::East),
-            self.moving_to(Dir::North),
-            self.moving_to(Dir::South),
-            self.moving_to(Dir::North).moving_to(Dir::West),
-            self.moving_to(Dir::North).moving_to(Dir::East),
-            self.moving_to(Dir::South).moving_to(Dir::West),
-            self.moving_to(Dir::South).moving_to(Dir::East),
-        ])
+        Dir8::all().map(move |dir| self + dir.into())
     }
 
     pub fn non_neg_diag_neighbors(self) -> impl Iterator<Item = Self> {
         self.diag_neighbors().filter(|p| p.is_non_neg())
     }
+
+    /// Rotates a quarter turn counterclockwise around the origin, so that `North` becomes
+    /// `West`.
+    pub fn rotate_left(self) -> Self {
+        Self {
+            x: self.y,
+            y: -self.x,
+        }
+    }
+
+    /// Rotates a quarter turn clockwise around the origin, so that `North` becomes `East`.
+    pub fn rotate_right(self) -> Self {
+        Self {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+}
+
+impl Add for Pos {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl AddAssign for Pos {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Pos {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+impl SubAssign for Pos {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i32> for Pos {
+    type Output = Self;
+    fn mul(self, factor: i32) -> Self {
+        Self {
+            x: self.x * factor,
+            y: self.y * factor,
+        }
+    }
+}
+
+impl Neg for Pos {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+/// The unit vector in a direction, with `y` increasing towards the south.
+impl From<Dir> for Pos {
+    fn from(dir: Dir) -> Self {
+        match dir {
+            Dir::North => Self { x: 0, y: -1 },
+            Dir::South => Self { x: 0, y: 1 },
+            Dir::West => Self { x: -1, y: 0 },
+            Dir::East => Self { x: 1, y: 0 },
+        }
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
@@ -72,6 +140,17 @@ impl Dir {
         iter!([Self::North, Self::South, Self::West, Self::East])
     }
 
+    /// Parses `U`/`D`/`L`/`R`, `N`/`S`/`W`/`E` and `^`/`v`/`<`/`>`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'U' | 'N' | '^' => Some(Self::North),
+            'D' | 'S' | 'v' => Some(Self::South),
+            'L' | 'W' | '<' => Some(Self::West),
+            'R' | 'E' | '>' => Some(Self::East),
+            _ => None,
+        }
+    }
+
     pub fn left(self) -> Self {
         match self {
             Self::North => Self::West,
@@ -101,3 +180,369 @@ impl Dir {
         *self = self.opposite()
     }
 }
+
+/// The four cardinal and the four diagonal directions, in clockwise order.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    const ALL: [Self; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        iter!(Self::ALL)
+    }
+
+    /// Turns 45 degrees counterclockwise.
+    pub fn left(self) -> Self {
+        Self::ALL[(self as usize + 7) % 8]
+    }
+
+    /// Turns 45 degrees clockwise.
+    pub fn right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self as usize + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+}
+
+impl From<Dir> for Dir8 {
+    fn from(dir: Dir) -> Self {
+        match dir {
+            Dir::North => Self::North,
+            Dir::South => Self::South,
+            Dir::West => Self::West,
+            Dir::East => Self::East,
+        }
+    }
+}
+
+impl From<Dir8> for Pos {
+    fn from(dir: Dir8) -> Self {
+        let (x, y) = match dir {
+            Dir8::North => (0, -1),
+            Dir8::NorthEast => (1, -1),
+            Dir8::East => (1, 0),
+            Dir8::SouthEast => (1, 1),
+            Dir8::South => (0, 1),
+            Dir8::SouthWest => (-1, 1),
+            Dir8::West => (-1, 0),
+            Dir8::NorthWest => (-1, -1),
+        };
+        Self { x, y }
+    }
+}
+
+/// A position on a hexagonal grid in axial coordinates, where the implicit third cube
+/// coordinate is `-q - r`. The same coordinates work for both flat-top and pointy-top
+/// hexagons, only the names of the directions differ.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    const OFFSETS: [Hex; 6] = [
+        Hex { q: 1, r: -1 },
+        Hex { q: 1, r: 0 },
+        Hex { q: 0, r: 1 },
+        Hex { q: -1, r: 1 },
+        Hex { q: -1, r: 0 },
+        Hex { q: 0, r: -1 },
+    ];
+
+    pub fn origin() -> Self {
+        Self { q: 0, r: 0 }
+    }
+
+    /// The third cube coordinate.
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// The number of steps between two hexes.
+    pub fn distance(self, other: Self) -> i32 {
+        let d = other - self;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        iter!(Self::OFFSETS).map(move |offset| self + offset)
+    }
+
+    /// Rotates 60 degrees clockwise around the origin.
+    pub fn rotate_right(self) -> Self {
+        Self {
+            q: -self.r,
+            r: -self.s(),
+        }
+    }
+
+    /// Rotates 60 degrees counterclockwise around the origin.
+    pub fn rotate_left(self) -> Self {
+        Self {
+            q: -self.s(),
+            r: -self.q,
+        }
+    }
+}
+
+impl Add for Hex {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            q: self.q + other.q,
+            r: self.r + other.r,
+        }
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self {
+            q: self.q - other.q,
+            r: self.r - other.r,
+        }
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Self;
+    fn mul(self, factor: i32) -> Self {
+        Self {
+            q: self.q * factor,
+            r: self.r * factor,
+        }
+    }
+}
+
+/// Stores `q` as `x` and `r` as `y`, so that hexes can be used with `Grid`.
+impl From<Hex> for Pos {
+    fn from(Hex { q, r }: Hex) -> Self {
+        Self { x: q, y: r }
+    }
+}
+
+impl From<Pos> for Hex {
+    fn from(Pos { x, y }: Pos) -> Self {
+        Self { q: x, r: y }
+    }
+}
+
+/// Parses a path of directions without separators, such as `nwwswee`, by trying the two
+/// letter directions first.
+fn parse_hex_path<D>(mut s: &str, parse: impl Fn(&str) -> Option<D>) -> Option<Vec<D>> {
+    let mut path = Vec::new();
+    while !s.is_empty() {
+        let (dir, len) = match s.get(..2).and_then(&parse) {
+            Some(dir) => (dir, 2),
+            None => (parse(s.get(..1)?)?, 1),
+        };
+        path.push(dir);
+        s = &s[len..];
+    }
+    Some(path)
+}
+
+/// The directions between flat-top hexagons, which are stacked in columns.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FlatDir {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl FlatDir {
+    pub fn all() -> impl Iterator<Item = Self> {
+        iter!([
+            Self::North,
+            Self::NorthEast,
+            Self::SouthEast,
+            Self::South,
+            Self::SouthWest,
+            Self::NorthWest,
+        ])
+    }
+
+    /// Parses `n`, `ne`, `se`, `s`, `sw` or `nw`, in either case.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "n" => Some(Self::North),
+            "ne" => Some(Self::NorthEast),
+            "se" => Some(Self::SouthEast),
+            "s" => Some(Self::South),
+            "sw" => Some(Self::SouthWest),
+            "nw" => Some(Self::NorthWest),
+            _ => None,
+        }
+    }
+
+    pub fn parse_path(s: &str) -> Option<Vec<Self>> {
+        parse_hex_path(s, Self::parse)
+    }
+}
+
+impl From<FlatDir> for Hex {
+    fn from(dir: FlatDir) -> Self {
+        let (q, r) = match dir {
+            FlatDir::North => (0, -1),
+            FlatDir::NorthEast => (1, -1),
+            FlatDir::SouthEast => (1, 0),
+            FlatDir::South => (0, 1),
+            FlatDir::SouthWest => (-1, 1),
+            FlatDir::NorthWest => (-1, 0),
+        };
+        Self { q, r }
+    }
+}
+
+/// The directions between pointy-top hexagons, which are laid out in rows.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum PointyDir {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl PointyDir {
+    pub fn all() -> impl Iterator<Item = Self> {
+        iter!([
+            Self::East,
+            Self::SouthEast,
+            Self::SouthWest,
+            Self::West,
+            Self::NorthWest,
+            Self::NorthEast,
+        ])
+    }
+
+    /// Parses `e`, `se`, `sw`, `w`, `nw` or `ne`, in either case.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "e" => Some(Self::East),
+            "se" => Some(Self::SouthEast),
+            "sw" => Some(Self::SouthWest),
+            "w" => Some(Self::West),
+            "nw" => Some(Self::NorthWest),
+            "ne" => Some(Self::NorthEast),
+            _ => None,
+        }
+    }
+
+    pub fn parse_path(s: &str) -> Option<Vec<Self>> {
+        parse_hex_path(s, Self::parse)
+    }
+}
+
+impl From<PointyDir> for Hex {
+    fn from(dir: PointyDir) -> Self {
+        let (q, r) = match dir {
+            PointyDir::East => (1, 0),
+            PointyDir::SouthEast => (0, 1),
+            PointyDir::SouthWest => (-1, 1),
+            PointyDir::West => (-1, 0),
+            PointyDir::NorthWest => (0, -1),
+            PointyDir::NorthEast => (1, -1),
+        };
+        Self { q, r }
+    }
+}
+
+#[test]
+fn test_pos() {
+    let pos = Pos { x: 3, y: -2 };
+    assert_eq!(pos + Pos { x: 1, y: 1 }, Pos { x: 4, y: -1 });
+    assert_eq!(pos - pos, Pos::origin());
+    assert_eq!(-pos * 2, Pos { x: -6, y: 4 });
+    assert_eq!(pos.rotate_left().rotate_right(), pos);
+    assert_eq!(pos.rotate_left().rotate_left(), -pos);
+
+    for dir in Dir::all() {
+        assert_eq!(Pos::from(dir).rotate_left(), Pos::from(dir.left()));
+        assert_eq!(Pos::from(dir).rotate_right(), Pos::from(dir.right()));
+        assert_eq!(Pos::from(Dir8::from(dir)), Pos::from(dir));
+    }
+    for dir in Dir8::all() {
+        assert_eq!(dir.right().left(), dir);
+        assert_eq!(Pos::from(dir.opposite()), -Pos::from(dir));
+    }
+
+    let dirs: Option<Vec<_>> = "URDL^>v<NESW".chars().map(Dir::from_char).collect();
+    assert_eq!(
+        dirs.unwrap(),
+        [Dir::North, Dir::East, Dir::South, Dir::West].repeat(3)
+    );
+    assert_eq!(Dir::from_char('x'), None);
+}
+
+#[test]
+fn test_hex() {
+    let walk = |path: &str| {
+        let dirs = path.split(',').map(|d| FlatDir::parse(d).unwrap());
+        dirs.fold(Hex::origin(), |hex, dir| hex + dir.into())
+    };
+    assert_eq!(walk("ne,ne,ne").distance(Hex::origin()), 3);
+    assert_eq!(walk("ne,ne,sw,sw"), Hex::origin());
+    assert_eq!(walk("ne,ne,s,s").distance(Hex::origin()), 2);
+    assert_eq!(walk("se,sw,se,sw,sw").distance(Hex::origin()), 3);
+
+    let path = PointyDir::parse_path("nwwswee").unwrap();
+    assert_eq!(path.len(), 5);
+    assert_eq!(
+        path.into_iter().fold(Hex::origin(), |h, d| h + d.into()),
+        Hex::origin()
+    );
+    assert_eq!(PointyDir::parse_path("nwx"), None);
+
+    for dir in PointyDir::all() {
+        let hex = Hex::from(dir);
+        assert_eq!(hex.distance(Hex::origin()), 1);
+        assert_eq!(hex.rotate_left().rotate_right(), hex);
+        assert!(Hex::origin().neighbors().any(|n| n == hex));
+    }
+    assert!(FlatDir::all().all(|d| Hex::from(d).distance(Hex::origin()) == 1));
+    assert_eq!(
+        Hex { q: 2, r: -1 }
+            .rotate_right()
+            .rotate_right()
+            .rotate_right(),
+        Hex { q: -2, r: 1 }
+    );
+}
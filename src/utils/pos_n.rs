@@ -0,0 +1,167 @@
+use super::Pos;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A position or vector with `N` coordinates.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PosN<const N: usize>(pub [i32; N]);
+
+pub type Pos3 = PosN<3>;
+pub type Pos4 = PosN<4>;
+
+impl<const N: usize> PosN<N> {
+    pub fn origin() -> Self {
+        Self([0; N])
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(i32, i32) -> i32) -> Self {
+        let mut result = self;
+        for i in 0..N {
+            result.0[i] = f(self.0[i], other.0[i]);
+        }
+        result
+    }
+
+    fn map(mut self, f: impl Fn(i32) -> i32) -> Self {
+        self.0.iter_mut().for_each(|x| *x = f(*x));
+        self
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> i32 {
+        (other - self).0.iter().map(|x| x.abs()).sum()
+    }
+
+    pub fn chebyshev_distance(self, other: Self) -> i32 {
+        (other - self).0.iter().map(|x| x.abs()).max().unwrap_or(0)
+    }
+
+    /// The sign of every coordinate.
+    pub fn signum(self) -> Self {
+        self.map(i32::signum)
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        self.zip_with(other, i32::min)
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        self.zip_with(other, i32::max)
+    }
+
+    /// All 3^N - 1 positions that differ by at most 1 in every coordinate.
+    pub fn neighbors(self) -> impl Iterator<Item = Self> {
+        let count = 3_usize.pow(N as u32);
+        (0..count)
+            .filter(move |&i| i != count / 2)
+            .map(move |mut i| {
+                let mut neighbor = self;
+                for x in &mut neighbor.0 {
+                    *x += (i % 3) as i32 - 1;
+                    i /= 3;
+                }
+                neighbor
+            })
+    }
+
+    /// The smallest and largest coordinates of the given positions, or `None` if there are
+    /// none.
+    pub fn bounding_box(positions: impl IntoIterator<Item = Self>) -> Option<(Self, Self)> {
+        let mut positions = positions.into_iter();
+        let first = positions.next()?;
+        Some(positions.fold((first, first), |(min, max), pos| {
+            (min.min(pos), max.max(pos))
+        }))
+    }
+
+    /// Whether `self` lies within the bounding box `min..=max`.
+    pub fn is_within(self, min: Self, max: Self) -> bool {
+        (0..N).all(|i| (min.0[i]..=max.0[i]).contains(&self.0[i]))
+    }
+}
+
+impl From<Pos> for PosN<2> {
+    fn from(Pos { x, y }: Pos) -> Self {
+        Self([x, y])
+    }
+}
+
+impl From<PosN<2>> for Pos {
+    fn from(PosN([x, y]): PosN<2>) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<const N: usize> Index<usize> for PosN<N> {
+    type Output = i32;
+    fn index(&self, i: usize) -> &i32 {
+        &self.0[i]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for PosN<N> {
+    fn index_mut(&mut self, i: usize) -> &mut i32 {
+        &mut self.0[i]
+    }
+}
+
+impl<const N: usize> Add for PosN<N> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a + b)
+    }
+}
+
+impl<const N: usize> AddAssign for PosN<N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<const N: usize> Sub for PosN<N> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        self.zip_with(other, |a, b| a - b)
+    }
+}
+
+impl<const N: usize> SubAssign for PosN<N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<const N: usize> Mul<i32> for PosN<N> {
+    type Output = Self;
+    fn mul(self, factor: i32) -> Self {
+        self.map(|x| x * factor)
+    }
+}
+
+impl<const N: usize> Neg for PosN<N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        self.map(|x| -x)
+    }
+}
+
+#[test]
+fn test_pos_n() {
+    let a = PosN([1, -2, 3]);
+    let b = PosN([4, 0, -1]);
+
+    assert_eq!(a + b, PosN([5, -2, 2]));
+    assert_eq!(a - b, PosN([-3, -2, 4]));
+    assert_eq!(-a * 2, PosN([-2, 4, -6]));
+    assert_eq!(a.manhattan_distance(b), 9);
+    assert_eq!(a.chebyshev_distance(b), 4);
+    assert_eq!((b - a).signum(), PosN([1, 1, -1]));
+    assert_eq!(
+        Pos3::bounding_box(vec![a, b]),
+        Some((PosN([1, -2, -1]), PosN([4, 0, 3])))
+    );
+
+    assert_eq!(Pos3::origin().neighbors().count(), 26);
+    assert_eq!(Pos4::origin().neighbors().count(), 80);
+    assert!(Pos4::origin()
+        .neighbors()
+        .all(|n| n != Pos4::origin() && n.chebyshev_distance(Pos4::origin()) == 1));
+}
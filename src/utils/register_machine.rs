@@ -0,0 +1,340 @@
+use num::traits::{PrimInt, Signed};
+use parser::prelude::*;
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    hash::Hash,
+    iter,
+};
+
+pub trait Word: PrimInt + Signed + Hash + Debug {}
+
+impl<W: PrimInt + Signed + Hash + Debug> Word for W {}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Register(pub usize);
+
+impl Display for Register {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", (b'a' + self.0 as u8) as char)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Source<W> {
+    Value(W),
+    Register(Register),
+}
+
+impl<W: Display> Display for Source<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Value(v) => write!(f, "{}", v),
+            Self::Register(r) => write!(f, "{}", r),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Regs<W, const N: usize>(pub [W; N]);
+
+impl<W: Word, const N: usize> Regs<W, N> {
+    pub fn new() -> Self {
+        Self([W::zero(); N])
+    }
+
+    pub fn read(&self, source: Source<W>) -> W {
+        match source {
+            Source::Value(v) => v,
+            Source::Register(r) => self.0[r.0],
+        }
+    }
+
+    pub fn write(&mut self, register: Register) -> &mut W {
+        &mut self.0[register.0]
+    }
+}
+
+impl<W: Display, const N: usize> Display for Regs<W, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, value) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}={}", Register(i), value)?;
+        }
+        Ok(())
+    }
+}
+
+/// What the instruction pointer should do after an instruction has executed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Flow<W> {
+    Next,
+    Jump(W),
+    Output(W),
+    Halt,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Step<W> {
+    Continue,
+    Output(W),
+    Halt,
+}
+
+pub trait Execute<W, const N: usize>: Copy {
+    fn execute(self, machine: &mut Machine<Self, W, N>) -> Flow<W>;
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine<I, W, const N: usize> {
+    pub instructions: Vec<I>,
+    pub ip: usize,
+    pub regs: Regs<W, N>,
+}
+
+impl<I, W, const N: usize> Machine<I, W, N>
+where
+    I: Execute<W, N>,
+    W: Word,
+{
+    pub fn new(instructions: Vec<I>) -> Self {
+        Self {
+            instructions,
+            ip: 0,
+            regs: Regs::new(),
+        }
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.ip >= self.instructions.len()
+    }
+
+    /// Executes a single instruction. Returns `Step::Halt` if the machine was already halted,
+    /// or if it halted because of this instruction without producing an output.
+    pub fn step(&mut self) -> Step<W> {
+        let instruction = match self.instructions.get(self.ip) {
+            None => return Step::Halt,
+            Some(&instruction) => instruction,
+        };
+
+        match instruction.execute(self) {
+            Flow::Next => self.ip += 1,
+            Flow::Jump(offset) => {
+                match (self.ip as isize).checked_add(offset.to_isize().unwrap()) {
+                    Some(new) if new >= 0 => self.ip = new as usize,
+                    _ => self.ip = self.instructions.len(),
+                }
+            }
+            Flow::Output(value) => {
+                self.ip += 1;
+                return Step::Output(value);
+            }
+            Flow::Halt => self.ip = self.instructions.len(),
+        }
+
+        if self.is_halted() {
+            Step::Halt
+        } else {
+            Step::Continue
+        }
+    }
+
+    pub fn run(&mut self) {
+        while self.step() != Step::Halt {}
+    }
+
+    pub fn outputs(&mut self) -> impl Iterator<Item = W> + '_ {
+        iter::from_fn(move || loop {
+            match self.step() {
+                Step::Continue => {}
+                Step::Output(value) => return Some(value),
+                Step::Halt => return None,
+            }
+        })
+    }
+
+    /// Executes one instruction per item until the machine halts.
+    pub fn trace(&mut self) -> impl Iterator<Item = Trace<I, W, N>> + '_ {
+        iter::from_fn(move || {
+            let ip = self.ip;
+            let instruction = *self.instructions.get(ip)?;
+            self.step();
+            Some(Trace {
+                ip,
+                instruction,
+                regs: self.regs.clone(),
+            })
+        })
+    }
+}
+
+/// An executed instruction, along with the registers right after executing it.
+#[derive(Debug, Clone)]
+pub struct Trace<I, W, const N: usize> {
+    pub ip: usize,
+    pub instruction: I,
+    pub regs: Regs<W, N>,
+}
+
+impl<I: Display, W: Display, const N: usize> Display for Trace<I, W, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let instruction = self.instruction.to_string();
+        write!(f, "{:>4}  {:<14}{}", self.ip, instruction, self.regs)
+    }
+}
+
+/// Displays a program with one instruction per line.
+pub struct Listing<'a, I>(pub &'a [I]);
+
+impl<I: Display> Display for Listing<'_, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for instruction in self.0 {
+            writeln!(f, "{}", instruction)?;
+        }
+        Ok(())
+    }
+}
+
+/// Displays two versions of the same program line by line, marking the changed lines with
+/// `-` and `+`.
+pub struct Diff<'a, I>(pub &'a [I], pub &'a [I]);
+
+impl<I: Display + PartialEq> Display for Diff<'_, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, (old, new)) in self.0.iter().zip(self.1).enumerate() {
+            if old == new {
+                writeln!(f, "{:>4}   {}", i, old)?;
+            } else {
+                writeln!(f, "{:>4} - {}", i, old)?;
+                writeln!(f, "{:>4} + {}", i, new)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn value<'a, W: Word>() -> impl Parser<&'a str, Output = W> + Copy + Clone {
+    parser::i64().and_then(W::from)
+}
+
+/// Parses one of the `N` registers named `a`, `b`, `c` and so on.
+pub fn register<'a, const N: usize>() -> impl Parser<&'a str, Output = Register> + Copy + Clone {
+    satisfy(|c: char| c.is_ascii()).and_then(|c| {
+        let i = (c as u8).wrapping_sub(b'a') as usize;
+        if i < N {
+            Some(Register(i))
+        } else {
+            None
+        }
+    })
+}
+
+pub fn source<'a, W: Word, const N: usize>(
+) -> impl Parser<&'a str, Output = Source<W>> + Copy + Clone {
+    choice((
+        value().map(|v| Source::Value(v)),
+        register::<N>().map(|r| Source::Register(r)),
+    ))
+}
+
+pub fn program<'a, I>(
+    instruction: impl Parser<&'a str, Output = I>,
+) -> impl Parser<&'a str, Output = Vec<I>> {
+    instruction.collect_sep_by(token('\n'))
+}
+
+/// Counts up in registers, outputs them and jumps around.
+#[cfg(test)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Toy {
+    Inc(Register),
+    Out(Register),
+    Jump(i32),
+}
+
+#[cfg(test)]
+impl<const N: usize> Execute<i32, N> for Toy {
+    fn execute(self, machine: &mut Machine<Self, i32, N>) -> Flow<i32> {
+        match self {
+            Toy::Inc(r) => {
+                *machine.regs.write(r) += 1;
+                Flow::Next
+            }
+            Toy::Out(r) => Flow::Output(machine.regs.read(Source::Register(r))),
+            Toy::Jump(offset) => Flow::Jump(offset),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Display for Toy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Toy::Inc(r) => write!(f, "inc {}", r),
+            Toy::Out(r) => write!(f, "out {}", r),
+            Toy::Jump(offset) => write!(f, "jmp {}", offset),
+        }
+    }
+}
+
+#[test]
+fn test_machine() {
+    let a = Register(0);
+    let program = vec![Toy::Inc(a), Toy::Out(a), Toy::Jump(-2)];
+    let mut machine = Machine::<_, i32, 1>::new(program);
+    let outputs: Vec<i32> = machine.outputs().take(3).collect();
+    assert_eq!(outputs, [1, 2, 3]);
+    assert!(!machine.is_halted());
+
+    // jumping before the first instruction halts
+    let mut machine = Machine::<_, i32, 1>::new(vec![Toy::Inc(a), Toy::Jump(-5), Toy::Inc(a)]);
+    machine.run();
+    assert!(machine.is_halted());
+    assert_eq!(machine.regs.0, [1]);
+
+    // and so does jumping past the last one
+    let mut machine =
+        Machine::<_, i32, 1>::new(vec![Toy::Jump(2), Toy::Inc(a), Toy::Out(a), Toy::Jump(7)]);
+    assert_eq!(machine.step(), Step::Continue);
+    assert_eq!(machine.step(), Step::Output(0));
+    assert_eq!(machine.step(), Step::Halt);
+    assert_eq!(machine.regs.0, [0]);
+    assert_eq!(machine.step(), Step::Halt);
+}
+
+#[test]
+fn test_display() {
+    let [a, b] = [Register(0), Register(1)];
+    let program = vec![Toy::Inc(a), Toy::Inc(b), Toy::Out(a), Toy::Jump(5)];
+
+    let mut machine = Machine::<_, i32, 2>::new(program.clone());
+    let trace: Vec<String> = machine.trace().map(|t| t.to_string()).collect();
+    assert_eq!(
+        trace,
+        [
+            "   0  inc a         a=1 b=0",
+            "   1  inc b         a=1 b=1",
+            "   2  out a         a=1 b=1",
+            "   3  jmp 5         a=1 b=1",
+        ]
+    );
+    assert!(machine.is_halted());
+
+    assert_eq!(
+        Listing(&program).to_string(),
+        "inc a\ninc b\nout a\njmp 5\n"
+    );
+
+    let patched = [Toy::Inc(a), Toy::Out(b), Toy::Out(a), Toy::Jump(-3)];
+    assert_eq!(
+        Diff(&program, &patched).to_string(),
+        "   0   inc a
+   1 - inc b
+   1 + out b
+   2   out a
+   3 - jmp 5
+   3 + jmp -3
+"
+    );
+}
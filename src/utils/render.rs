@@ -0,0 +1,175 @@
+use super::{DenseGrid, Grid, Pos};
+use std::{
+    cmp,
+    fmt::{self, Display, Formatter, Write},
+};
+
+/// A grid that can be drawn one character per tile.
+pub trait Drawable {
+    type Tile;
+
+    /// The tile at `pos`, or `None` if the grid has no tile there.
+    fn tile(&self, pos: Pos) -> Option<&Self::Tile>;
+
+    /// The smallest and largest coordinates of the grid, or `None` if it's empty.
+    fn extent(&self) -> Option<(Pos, Pos)>;
+
+    fn render<F>(&self, f: F) -> Render<'_, Self, F>
+    where
+        F: Fn(&Self::Tile) -> char,
+    {
+        Render {
+            grid: self,
+            f,
+            bounds: None,
+            labels: false,
+        }
+    }
+}
+
+impl<T> Drawable for Grid<T> {
+    type Tile = T;
+
+    fn tile(&self, pos: Pos) -> Option<&T> {
+        Some(&self[pos])
+    }
+
+    fn extent(&self) -> Option<(Pos, Pos)> {
+        self.bounds()
+    }
+}
+
+impl<T> Drawable for DenseGrid<T> {
+    type Tile = T;
+
+    fn tile(&self, pos: Pos) -> Option<&T> {
+        self.get(pos)
+    }
+
+    fn extent(&self) -> Option<(Pos, Pos)> {
+        if self.width() == 0 || self.height() == 0 {
+            None
+        } else {
+            let max = Pos {
+                x: self.width() as i32 - 1,
+                y: self.height() as i32 - 1,
+            };
+            Some((Pos::origin(), max))
+        }
+    }
+}
+
+/// Draws `#` for `true` and `.` for `false`.
+pub fn pixel(&on: &bool) -> char {
+    if on {
+        '#'
+    } else {
+        '.'
+    }
+}
+
+pub struct Render<'a, G: ?Sized, F> {
+    grid: &'a G,
+    f: F,
+    bounds: Option<(Pos, Pos)>,
+    labels: bool,
+}
+
+impl<G, F> Render<'_, G, F>
+where
+    G: Drawable + ?Sized,
+    F: Fn(&G::Tile) -> char,
+{
+    /// Draws the tiles from `min` to `max` inclusive, instead of the extent of the grid.
+    /// Positions without a tile are drawn as spaces.
+    pub fn bounds(mut self, min: Pos, max: Pos) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    /// Draws the `y` coordinate in front of every row, and the `x` coordinates vertically
+    /// above every column.
+    pub fn labels(mut self) -> Self {
+        self.labels = true;
+        self
+    }
+
+    pub fn write_to(&self, w: &mut impl Write) -> fmt::Result {
+        let (min, max) = match self.bounds.or_else(|| self.grid.extent()) {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+
+        let label_width = |a: i32, b: i32| cmp::max(a.to_string().len(), b.to_string().len());
+        let margin = if self.labels {
+            label_width(min.y, max.y) + 1
+        } else {
+            0
+        };
+
+        if self.labels {
+            let height = label_width(min.x, max.x);
+            let labels: Vec<Vec<char>> = (min.x..=max.x)
+                .map(|x| format!("{:>1$}", x, height).chars().collect())
+                .collect();
+            for i in 0..height {
+                write!(w, "{:1$}", "", margin)?;
+                for label in &labels {
+                    w.write_char(label[i])?;
+                }
+                writeln!(w)?;
+            }
+        }
+
+        for y in min.y..=max.y {
+            if self.labels {
+                write!(w, "{:>1$} ", y, margin - 1)?;
+            }
+            for x in min.x..=max.x {
+                let c = self.grid.tile(Pos { x, y }).map_or(' ', &self.f);
+                w.write_char(c)?;
+            }
+            writeln!(w)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<G, F> Display for Render<'_, G, F>
+where
+    G: Drawable + ?Sized,
+    F: Fn(&G::Tile) -> char,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
+
+#[test]
+fn test_render() {
+    let mut grid = Grid::new(false);
+    grid[Pos { x: 0, y: 0 }] = true;
+    grid[Pos { x: 2, y: 1 }] = true;
+
+    assert_eq!(grid.render(pixel).to_string(), "#..\n..#\n");
+    assert_eq!(
+        grid.render(pixel)
+            .bounds(Pos { x: -1, y: 0 }, Pos { x: 10, y: 1 })
+            .labels()
+            .to_string(),
+        "  -          1\n  \
+         101234567890\n\
+         0 .#..........\n\
+         1 ...#........\n"
+    );
+
+    let dense = DenseGrid::from_fn(2, 2, |pos| pos.x == pos.y);
+    assert_eq!(
+        dense
+            .render(pixel)
+            .bounds(Pos::origin(), Pos { x: 2, y: 1 })
+            .to_string(),
+        "#. \n.# \n"
+    );
+}
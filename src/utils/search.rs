@@ -1,10 +1,27 @@
 use num::traits::Zero;
 use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashSet},
+    cmp::{self, Ordering},
+    collections::{BinaryHeap, HashMap},
+    convert::TryInto,
     hash::Hash,
+    time::{Duration, Instant},
 };
 
+/// Counters of the work done by a search.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct SearchStats {
+    /// The number of settled nodes whose neighbors were generated.
+    pub expanded: usize,
+    /// The number of heap entries that were skipped because their node had already been
+    /// settled.
+    pub duplicates: usize,
+    pub peak_heap_len: usize,
+    pub elapsed: Duration,
+}
+
+/// Nodes that were found by a search, along with their cost.
+type Nodes<D> = (Vec<<D as Dijkstra>::Node>, <D as Dijkstra>::Cost);
+
 pub trait Dijkstra {
     type Node: Eq + Hash + Clone;
     type Cost: Copy + Ord + Zero;
@@ -13,51 +30,380 @@ pub trait Dijkstra {
     fn is_goal(&self, node: &Self::Node) -> bool;
     fn neighbors(&self, node: &Self::Node, add: impl FnMut(Self::Node, Self::Cost));
 
+    /// A lower bound on the cost of reaching a goal from `node`, which turns the search
+    /// into A*. It must never decrease by more than the cost of an edge, or nodes may be
+    /// settled too early; debug builds panic when that happens.
+    fn heuristic(&self, _node: &Self::Node) -> Self::Cost {
+        Self::Cost::zero()
+    }
+
     fn run(&self) -> Option<Self::Cost> {
-        let mut heap = BinaryHeap::new();
-        let mut seen = HashSet::new();
-        heap.push(Wrapper {
-            node: self.start(),
-            cost: Self::Cost::zero(),
+        nearest_goal::<Self, Heap<Self>>(self, None)
+    }
+
+    fn run_with_stats(&self) -> (Option<Self::Cost>, SearchStats) {
+        let mut stats = SearchStats::default();
+        (
+            nearest_goal::<Self, Heap<Self>>(self, Some(&mut stats)),
+            stats,
+        )
+    }
+
+    /// The nodes from the start to the nearest goal, inclusive, and the cost of reaching it.
+    fn run_with_path(&self) -> Option<(Vec<Self::Node>, Self::Cost)> {
+        path_to_nearest_goal::<Self, Heap<Self>>(self, None)
+    }
+
+    fn run_with_path_and_stats(&self) -> (Option<Nodes<Self>>, SearchStats) {
+        let mut stats = SearchStats::default();
+        (
+            path_to_nearest_goal::<Self, Heap<Self>>(self, Some(&mut stats)),
+            stats,
+        )
+    }
+
+    /// The cost of reaching every node that can be reached from the start. Goals are
+    /// ignored, so the graph has to be finite.
+    fn distances_from_start(&self) -> HashMap<Self::Node, Self::Cost> {
+        all_distances::<Self, Heap<Self>>(self, None)
+    }
+
+    fn distances_from_start_with_stats(&self) -> (HashMap<Self::Node, Self::Cost>, SearchStats) {
+        let mut stats = SearchStats::default();
+        (
+            all_distances::<Self, Heap<Self>>(self, Some(&mut stats)),
+            stats,
+        )
+    }
+
+    /// All goals that can be reached at the minimal cost, in the order in which they were
+    /// found, along with that cost.
+    fn run_all_goals(&self) -> Option<(Vec<Self::Node>, Self::Cost)> {
+        nearest_goals::<Self, Heap<Self>>(self, None)
+    }
+
+    fn run_all_goals_with_stats(&self) -> (Option<Nodes<Self>>, SearchStats) {
+        let mut stats = SearchStats::default();
+        (
+            nearest_goals::<Self, Heap<Self>>(self, Some(&mut stats)),
+            stats,
+        )
+    }
+}
+
+fn nearest_goal<D, Q>(dijkstra: &D, stats: Option<&mut SearchStats>) -> Option<D::Cost>
+where
+    D: Dijkstra + ?Sized,
+    Q: Queue<D>,
+{
+    let mut result = None;
+    explore::<D, Q>(dijkstra, false, stats, |node, cost, _| {
+        if dijkstra.is_goal(node) {
+            result = Some(cost);
+            false
+        } else {
+            true
+        }
+    });
+    result
+}
+
+fn path_to_nearest_goal<D, Q>(dijkstra: &D, stats: Option<&mut SearchStats>) -> Option<Nodes<D>>
+where
+    D: Dijkstra + ?Sized,
+    Q: Queue<D>,
+{
+    let mut parents = HashMap::new();
+    let mut goal = None;
+    explore::<D, Q>(dijkstra, true, stats, |node, cost, parent| {
+        if let Some(parent) = parent {
+            parents.insert(node.clone(), parent);
+        }
+        if dijkstra.is_goal(node) {
+            goal = Some((node.clone(), cost));
+            false
+        } else {
+            true
+        }
+    });
+
+    let (mut node, cost) = goal?;
+    let mut path = vec![node.clone()];
+    while let Some(parent) = parents.remove(&node) {
+        path.push(parent.clone());
+        node = parent;
+    }
+    path.reverse();
+    Some((path, cost))
+}
+
+fn all_distances<D, Q>(dijkstra: &D, stats: Option<&mut SearchStats>) -> HashMap<D::Node, D::Cost>
+where
+    D: Dijkstra + ?Sized,
+    Q: Queue<D>,
+{
+    let mut distances = HashMap::new();
+    explore::<D, Q>(dijkstra, false, stats, |node, cost, _| {
+        distances.insert(node.clone(), cost);
+        true
+    });
+    distances
+}
+
+fn nearest_goals<D, Q>(dijkstra: &D, stats: Option<&mut SearchStats>) -> Option<Nodes<D>>
+where
+    D: Dijkstra + ?Sized,
+    Q: Queue<D>,
+{
+    let mut goals = Vec::new();
+    let mut min_cost = None;
+    explore::<D, Q>(dijkstra, false, stats, |node, cost, _| {
+        if min_cost.map_or(false, |min| cost > min) {
+            return false;
+        }
+        if dijkstra.is_goal(node) {
+            min_cost = Some(cost);
+            goals.push(node.clone());
+        }
+        true
+    });
+    Some((goals, min_cost?))
+}
+
+/// Settles the nodes in order of increasing cost plus heuristic, passing each one to
+/// `settle` along with the node it was reached from if `track_parents` is set, until
+/// `settle` returns `false`. The search is only timed if `stats` are requested.
+fn explore<D, Q>(
+    dijkstra: &D,
+    track_parents: bool,
+    stats_out: Option<&mut SearchStats>,
+    mut settle: impl FnMut(&D::Node, D::Cost, Option<D::Node>) -> bool,
+) where
+    D: Dijkstra + ?Sized,
+    Q: Queue<D>,
+{
+    let started = stats_out.as_ref().map(|_| Instant::now());
+    let mut stats = SearchStats::default();
+    let mut settled = HashMap::new();
+    let start = dijkstra.start();
+    let priority = dijkstra.heuristic(&start);
+    let mut queue = Q::default();
+    queue.push_entry(Wrapper {
+        priority,
+        cost: D::Cost::zero(),
+        node: (start, None),
+    });
+    stats.peak_heap_len = queue.len();
+
+    while let Some(Wrapper {
+        node: (node, parent),
+        cost,
+        priority,
+    }) = queue.pop_entry()
+    {
+        if let Some(&settled_cost) = settled.get(&node) {
+            debug_assert!(
+                cost >= settled_cost,
+                "inconsistent heuristic: a node was settled before its cheapest path was found"
+            );
+            stats.duplicates += 1;
+            continue;
+        }
+        settled.insert(node.clone(), cost);
+
+        if !settle(&node, cost, parent) {
+            break;
+        }
+        stats.expanded += 1;
+        dijkstra.neighbors(&node, |neighbor, c| {
+            let cost = cost + c;
+            let parent = if track_parents {
+                Some(node.clone())
+            } else {
+                None
+            };
+            let entry = Wrapper {
+                priority: cost + dijkstra.heuristic(&neighbor),
+                cost,
+                node: (neighbor, parent),
+            };
+            debug_assert!(
+                entry.priority >= priority,
+                "inconsistent heuristic: it decreased by more than the cost of an edge"
+            );
+            queue.push_entry(entry);
         });
-        // let mut count = 0;
-        // let mut max_len = 0;
+        stats.peak_heap_len = cmp::max(stats.peak_heap_len, queue.len());
+    }
 
-        while let Some(Wrapper { node, cost }) = heap.pop() {
-            if !seen.insert(node.clone()) {
-                continue;
-            }
-            // count += 1;
-            if self.is_goal(&node) {
-                // dbg!(count);
-                // dbg!(max_len);
-                return Some(cost);
-            }
-            self.neighbors(&node, |node, c| {
-                heap.push(Wrapper {
-                    node,
-                    cost: cost + c,
-                })
-            });
-            // max_len = std::cmp::max(max_len, heap.len());
+    if let (Some(stats_out), Some(started)) = (stats_out, started) {
+        stats.elapsed = started.elapsed();
+        *stats_out = stats;
+    }
+}
+
+/// Runs a search with a `BucketQueue` instead of a binary heap, using the priorities (a cost
+/// plus its heuristic) themselves as buckets, so they have to be small non-negative
+/// integers. This pays off when the priorities rarely decrease from one popped node to the
+/// next.
+pub struct Bucketed<D>(pub D);
+
+impl<D> Bucketed<D>
+where
+    D: Dijkstra,
+    D::Cost: TryInto<usize>,
+{
+    pub fn run(&self) -> Option<D::Cost> {
+        nearest_goal::<D, Buckets<D>>(&self.0, None)
+    }
+
+    pub fn run_with_stats(&self) -> (Option<D::Cost>, SearchStats) {
+        let mut stats = SearchStats::default();
+        (
+            nearest_goal::<D, Buckets<D>>(&self.0, Some(&mut stats)),
+            stats,
+        )
+    }
+
+    pub fn run_with_path(&self) -> Option<Nodes<D>> {
+        path_to_nearest_goal::<D, Buckets<D>>(&self.0, None)
+    }
+
+    pub fn run_with_path_and_stats(&self) -> (Option<Nodes<D>>, SearchStats) {
+        let mut stats = SearchStats::default();
+        let path = path_to_nearest_goal::<D, Buckets<D>>(&self.0, Some(&mut stats));
+        (path, stats)
+    }
+
+    pub fn distances_from_start(&self) -> HashMap<D::Node, D::Cost> {
+        all_distances::<D, Buckets<D>>(&self.0, None)
+    }
+
+    pub fn distances_from_start_with_stats(&self) -> (HashMap<D::Node, D::Cost>, SearchStats) {
+        let mut stats = SearchStats::default();
+        let distances = all_distances::<D, Buckets<D>>(&self.0, Some(&mut stats));
+        (distances, stats)
+    }
+
+    pub fn run_all_goals(&self) -> Option<Nodes<D>> {
+        nearest_goals::<D, Buckets<D>>(&self.0, None)
+    }
+
+    pub fn run_all_goals_with_stats(&self) -> (Option<Nodes<D>>, SearchStats) {
+        let mut stats = SearchStats::default();
+        let goals = nearest_goals::<D, Buckets<D>>(&self.0, Some(&mut stats));
+        (goals, stats)
+    }
+}
+
+/// A queue entry of a search over `D`: a node, the node it was reached from, and its cost.
+type Entry<D> =
+    Wrapper<(<D as Dijkstra>::Node, Option<<D as Dijkstra>::Node>), <D as Dijkstra>::Cost>;
+type Heap<D> = BinaryHeap<Entry<D>>;
+type Buckets<D> = BucketQueue<Entry<D>>;
+
+/// The queues that `explore` can settle nodes from, in order of increasing priority.
+trait Queue<D: Dijkstra + ?Sized>: Default {
+    fn push_entry(&mut self, entry: Entry<D>);
+    fn pop_entry(&mut self) -> Option<Entry<D>>;
+    fn len(&self) -> usize;
+}
+
+impl<D: Dijkstra + ?Sized> Queue<D> for Heap<D> {
+    fn push_entry(&mut self, entry: Entry<D>) {
+        self.push(entry);
+    }
+
+    fn pop_entry(&mut self) -> Option<Entry<D>> {
+        self.pop()
+    }
+
+    fn len(&self) -> usize {
+        BinaryHeap::len(self)
+    }
+}
+
+impl<D> Queue<D> for Buckets<D>
+where
+    D: Dijkstra + ?Sized,
+    D::Cost: TryInto<usize>,
+{
+    fn push_entry(&mut self, entry: Entry<D>) {
+        match entry.priority.try_into() {
+            Ok(bucket) => self.push(bucket, entry),
+            Err(_) => panic!("a priority isn't a valid bucket"),
+        }
+    }
+
+    fn pop_entry(&mut self) -> Option<Entry<D>> {
+        self.pop().map(|(_, entry)| entry)
+    }
+
+    fn len(&self) -> usize {
+        BucketQueue::len(self)
+    }
+}
+
+/// A priority queue for small non-negative integer priorities, with a bucket per priority.
+/// Items with the same priority are popped in reverse order. It's fastest when no item is
+/// pushed with a lower priority than the last one that was popped, as is the case in
+/// Dijkstra's algorithm.
+pub struct BucketQueue<T> {
+    buckets: Vec<Vec<T>>,
+    current: usize,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            buckets: Vec::new(),
+            current: 0,
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, priority: usize, item: T) {
+        if priority >= self.buckets.len() {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(item);
+        self.current = cmp::min(self.current, priority);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        if self.len == 0 {
+            return None;
+        }
+        while self.buckets[self.current].is_empty() {
+            self.current += 1;
         }
-        None
-        // let (_, cost) = dijkstra(
-        //     &self.start(),
-        //     |node| {
-        //         let mut vec = Vec::new();
-        //         self.neighbors(node, |neighbor, cost| vec.push((neighbor, cost)));
-        //         vec
-        //     },
-        //     |node| self.is_goal(node),
-        // )?;
-        // Some(cost)
+        self.len -= 1;
+        let item = self.buckets[self.current].pop()?;
+        Some((self.current, item))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 }
 
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A heap entry, ordered so that the lowest priority is popped first.
 struct Wrapper<N, C> {
     node: N,
     cost: C,
+    priority: C,
 }
 
 impl<N, C> PartialEq for Wrapper<N, C>
@@ -65,7 +411,7 @@ where
     C: PartialEq,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.cost == other.cost
+        self.priority == other.priority
     }
 }
 
@@ -76,7 +422,7 @@ where
     C: Ord,
 {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.cost.cmp(&other.cost).reverse()
+        self.priority.cmp(&other.priority).reverse()
     }
 }
 
@@ -88,3 +434,172 @@ where
         Some(self.cmp(other))
     }
 }
+
+#[test]
+fn test_dijkstra() {
+    struct Graph {
+        edges: &'static [(u32, u32, u32)],
+        goals: &'static [u32],
+    }
+
+    impl Dijkstra for Graph {
+        type Node = u32;
+        type Cost = u32;
+
+        fn start(&self) -> u32 {
+            0
+        }
+
+        fn is_goal(&self, node: &u32) -> bool {
+            self.goals.contains(node)
+        }
+
+        fn neighbors(&self, &node: &u32, mut add: impl FnMut(u32, u32)) {
+            for &(from, to, cost) in self.edges {
+                if from == node {
+                    add(to, cost);
+                }
+            }
+        }
+    }
+
+    const EDGES: &[(u32, u32, u32)] = &[
+        (0, 1, 1),
+        (0, 2, 4),
+        (1, 2, 2),
+        (1, 3, 6),
+        (2, 3, 3),
+        (2, 4, 5),
+        (3, 5, 1),
+        (3, 6, 1),
+        (4, 5, 3),
+    ];
+
+    let graph = Graph {
+        edges: EDGES,
+        goals: &[4, 5],
+    };
+    assert_eq!(graph.run(), Some(7));
+    assert_eq!(graph.run_with_path(), Some((vec![0, 1, 2, 3, 5], 7)));
+    assert_eq!(graph.run_all_goals(), Some((vec![5], 7)));
+
+    let distances = graph.distances_from_start();
+    assert_eq!(distances.len(), 7);
+    assert_eq!(distances[&4], 8);
+
+    let graph = Graph {
+        edges: EDGES,
+        goals: &[5, 6],
+    };
+    let (mut goals, cost) = graph.run_all_goals().unwrap();
+    goals.sort();
+    assert_eq!((goals, cost), (vec![5, 6], 7));
+
+    let graph = Graph {
+        edges: EDGES,
+        goals: &[9],
+    };
+    assert_eq!(graph.run_with_path(), None);
+    assert_eq!(graph.run_all_goals(), None);
+
+    let (cost, stats) = graph.run_with_stats();
+    assert_eq!(cost, None);
+    assert_eq!(
+        (stats.expanded, stats.duplicates, stats.peak_heap_len),
+        (7, 3, 4)
+    );
+    let (path, stats) = graph.run_with_path_and_stats();
+    assert_eq!((path, stats.expanded), (None, 7));
+    let (goals, stats) = graph.run_all_goals_with_stats();
+    assert_eq!((goals, stats.expanded), (None, 7));
+    let (distances, stats) = graph.distances_from_start_with_stats();
+    assert_eq!(
+        (distances.len(), stats.expanded, stats.duplicates),
+        (7, 7, 3)
+    );
+}
+
+/// Walks along the number line in steps of 1, or jumps 3 to the right for 2.
+#[cfg(test)]
+struct Walk {
+    goal: i32,
+    heuristic: fn(i32) -> i32,
+}
+
+#[cfg(test)]
+impl Dijkstra for Walk {
+    type Node = i32;
+    type Cost = i32;
+
+    fn start(&self) -> i32 {
+        0
+    }
+
+    fn is_goal(&self, &node: &i32) -> bool {
+        node == self.goal
+    }
+
+    fn neighbors(&self, &node: &i32, mut add: impl FnMut(i32, i32)) {
+        add(node - 1, 1);
+        add(node + 1, 1);
+        add(node + 3, 2);
+    }
+
+    fn heuristic(&self, &node: &i32) -> i32 {
+        (self.heuristic)(node)
+    }
+}
+
+#[test]
+fn test_astar() {
+    let walk = Walk {
+        goal: 20,
+        heuristic: |node| cmp::max(0, (2 * (20 - node) + 2) / 3),
+    };
+    assert_eq!(walk.run(), Some(14));
+    let (path, cost) = walk.run_with_path().unwrap();
+    assert_eq!((path.len(), cost), (9, 14));
+
+    let dijkstra = Walk {
+        goal: 20,
+        heuristic: |_| 0,
+    };
+    assert_eq!(dijkstra.run(), Some(14));
+
+    let walk = Bucketed(walk);
+    assert_eq!(walk.run(), Some(14));
+    let (path, cost) = walk.run_with_path().unwrap();
+    assert_eq!((path.len(), cost), (9, 14));
+    assert_eq!(Bucketed(dijkstra).run_with_stats().0, Some(14));
+}
+
+#[test]
+fn test_bucket_queue() {
+    use std::iter;
+
+    let mut queue = BucketQueue::new();
+    assert!(queue.is_empty());
+    queue.push(3, 'a');
+    queue.push(1, 'b');
+    queue.push(3, 'c');
+    assert_eq!(queue.pop(), Some((1, 'b')));
+    // a priority below the last popped one is still popped first
+    queue.push(0, 'd');
+    queue.push(2, 'e');
+    assert_eq!(queue.len(), 4);
+    let rest: Vec<_> = iter::from_fn(|| queue.pop()).collect();
+    assert_eq!(rest, [(0, 'd'), (2, 'e'), (3, 'c'), (3, 'a')]);
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "inconsistent heuristic")]
+fn test_inconsistent_heuristic() {
+    // admissible, but it makes 6 get settled at 5 before the path through 3 is explored
+    let walk = Walk {
+        goal: 20,
+        heuristic: |node| if node == 3 { 11 } else { 0 },
+    };
+    walk.run();
+}
@@ -0,0 +1,283 @@
+use super::{DenseGrid, Dir, Grid, Pos};
+
+/// One of the eight symmetries of a square: an optional mirror image in the vertical axis,
+/// followed by a number of clockwise quarter turns.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Transform {
+    flip: bool,
+    turns: u8,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self::new(false, 0);
+    pub const ROTATE_RIGHT: Self = Self::new(false, 1);
+    pub const ROTATE_180: Self = Self::new(false, 2);
+    pub const ROTATE_LEFT: Self = Self::new(false, 3);
+    /// Swaps left and right.
+    pub const FLIP_HORIZONTAL: Self = Self::new(true, 0);
+    /// Mirrors in the anti-diagonal.
+    pub const ANTI_TRANSPOSE: Self = Self::new(true, 1);
+    /// Swaps top and bottom.
+    pub const FLIP_VERTICAL: Self = Self::new(true, 2);
+    /// Mirrors in the main diagonal.
+    pub const TRANSPOSE: Self = Self::new(true, 3);
+
+    pub const ALL: [Self; 8] = [
+        Self::IDENTITY,
+        Self::ROTATE_RIGHT,
+        Self::ROTATE_180,
+        Self::ROTATE_LEFT,
+        Self::FLIP_HORIZONTAL,
+        Self::ANTI_TRANSPOSE,
+        Self::FLIP_VERTICAL,
+        Self::TRANSPOSE,
+    ];
+
+    const fn new(flip: bool, turns: u8) -> Self {
+        Self { flip, turns }
+    }
+
+    /// Transforms `pos` around the origin.
+    pub fn apply(self, pos: Pos) -> Pos {
+        let mut pos = if self.flip {
+            Pos { x: -pos.x, ..pos }
+        } else {
+            pos
+        };
+        for _ in 0..self.turns {
+            pos = pos.rotate_right();
+        }
+        pos
+    }
+
+    pub fn inverse(self) -> Self {
+        if self.flip {
+            self
+        } else {
+            Self::new(false, (4 - self.turns) % 4)
+        }
+    }
+
+    /// The transform that applies `self` and then `other`.
+    pub fn then(self, other: Self) -> Self {
+        let turns = if other.flip {
+            4 + other.turns - self.turns
+        } else {
+            other.turns + self.turns
+        };
+        Self::new(self.flip != other.flip, turns % 4)
+    }
+
+    /// Whether the transform swaps the width and height of a grid.
+    pub fn is_sideways(self) -> bool {
+        self.turns % 2 == 1
+    }
+}
+
+impl<T: Clone> DenseGrid<T> {
+    pub fn transform(&self, transform: Transform) -> Self {
+        let max = Pos {
+            x: self.width() as i32 - 1,
+            y: self.height() as i32 - 1,
+        };
+        let (a, b) = (transform.apply(Pos::origin()), transform.apply(max));
+        let min = Pos {
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+        };
+        let (width, height) = if transform.is_sideways() {
+            (self.height(), self.width())
+        } else {
+            (self.width(), self.height())
+        };
+        let inverse = transform.inverse();
+        Self::from_fn(width, height, |pos| self[inverse.apply(pos + min)].clone())
+    }
+
+    /// The grid in each of its eight orientations, in the order of `Transform::ALL`.
+    pub fn orientations(&self) -> impl Iterator<Item = Self> + '_ {
+        Transform::ALL.iter().map(move |&t| self.transform(t))
+    }
+
+    /// The tiles from `min` to `max` inclusive, with `min` moved to the origin.
+    pub fn sub_grid(&self, min: Pos, max: Pos) -> Self {
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        Self::from_fn(width, height, |pos| self[pos + min].clone())
+    }
+
+    /// The tiles along one side of the grid, from left to right or from top to bottom.
+    pub fn border(&self, side: Dir) -> Vec<T> {
+        match side {
+            Dir::North => self.row(0).to_vec(),
+            Dir::South => self.row(self.height() - 1).to_vec(),
+            Dir::West => self.column(0).cloned().collect(),
+            Dir::East => self.column(self.width() - 1).cloned().collect(),
+        }
+    }
+
+    /// Cuts the grid into tiles of `width` by `height`, which should divide its size.
+    pub fn split(&self, width: usize, height: usize) -> DenseGrid<Self> {
+        assert!(
+            self.width() % width == 0 && self.height() % height == 0,
+            "a {}x{} grid can't be split into {}x{} tiles",
+            self.width(),
+            self.height(),
+            width,
+            height
+        );
+        let size = Pos {
+            x: width as i32,
+            y: height as i32,
+        };
+        DenseGrid::from_fn(
+            self.width() / width,
+            self.height() / height,
+            |Pos { x, y }| {
+                let min = Pos {
+                    x: x * size.x,
+                    y: y * size.y,
+                };
+                self.sub_grid(min, min + size - Pos { x: 1, y: 1 })
+            },
+        )
+    }
+
+    /// Joins tiles of equal size into a single grid. The inverse of `split`.
+    pub fn stitch(tiles: &DenseGrid<Self>) -> Self {
+        let (width, height) = tiles
+            .get(Pos::origin())
+            .map_or((0, 0), |tile| (tile.width(), tile.height()));
+        assert!(
+            tiles
+                .iter()
+                .all(|(_, tile)| (tile.width(), tile.height()) == (width, height)),
+            "all tiles should be {}x{}",
+            width,
+            height
+        );
+        let (w, h) = (width as i32, height as i32);
+        Self::from_fn(
+            tiles.width() * width,
+            tiles.height() * height,
+            |Pos { x, y }| {
+                let tile = &tiles[Pos { x: x / w, y: y / h }];
+                tile[Pos { x: x % w, y: y % h }].clone()
+            },
+        )
+    }
+
+    /// Moves the tiles of row `y` to the right by `by` positions, wrapping around.
+    pub fn rotate_row(&mut self, y: usize, by: usize) {
+        let width = self.width().max(1);
+        self.row_mut(y).rotate_right(by % width);
+    }
+
+    /// Moves the tiles of column `x` down by `by` positions, wrapping around.
+    pub fn rotate_column(&mut self, x: usize, by: usize) {
+        let mut column: Vec<T> = self.column(x).cloned().collect();
+        column.rotate_right(by % self.height().max(1));
+        for (y, tile) in (0..).zip(column) {
+            self[Pos { x: x as i32, y }] = tile;
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Moves every tile that has been written to, transforming its position around the
+    /// origin.
+    pub fn transform(&self, transform: Transform) -> Self {
+        let mut grid = Self::new(self.default().clone());
+        for (pos, tile) in self.iter() {
+            grid[transform.apply(pos)] = tile.clone();
+        }
+        grid
+    }
+
+    /// The tiles from `min` to `max` inclusive that have been written to, at their original
+    /// positions.
+    pub fn sub_grid(&self, min: Pos, max: Pos) -> Self {
+        let mut grid = Self::new(self.default().clone());
+        for (pos, tile) in self.iter() {
+            if (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y) {
+                grid[pos] = tile.clone();
+            }
+        }
+        grid
+    }
+
+    /// The tiles along one side of the bounds, from left to right or from top to bottom.
+    pub fn border(&self, side: Dir) -> Vec<T> {
+        let (min, max) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return Vec::new(),
+        };
+        let positions: Box<dyn Iterator<Item = Pos>> = match side {
+            Dir::North => Box::new((min.x..=max.x).map(|x| Pos { x, y: min.y })),
+            Dir::South => Box::new((min.x..=max.x).map(|x| Pos { x, y: max.y })),
+            Dir::West => Box::new((min.y..=max.y).map(|y| Pos { x: min.x, y })),
+            Dir::East => Box::new((min.y..=max.y).map(|y| Pos { x: max.x, y })),
+        };
+        positions.map(|pos| self[pos].clone()).collect()
+    }
+}
+
+#[test]
+fn test_transform() {
+    use std::collections::HashMap;
+
+    let grid = DenseGrid::from_fn(3, 2, |Pos { x, y }| x + 10 * y);
+    let rows = |grid: &DenseGrid<i32>| grid.rows().map(<[_]>::to_vec).collect::<Vec<_>>();
+    assert_eq!(
+        rows(&grid.transform(Transform::ROTATE_RIGHT)),
+        [[10, 0], [11, 1], [12, 2]]
+    );
+    assert_eq!(
+        rows(&grid.transform(Transform::FLIP_HORIZONTAL)),
+        [[2, 1, 0], [12, 11, 10]]
+    );
+    assert_eq!(
+        rows(&grid.transform(Transform::TRANSPOSE)),
+        [[0, 10], [1, 11], [2, 12]]
+    );
+    assert_eq!(grid.border(Dir::East), [2, 12]);
+    assert_eq!(
+        DenseGrid::stitch(&grid.split(1, 2)),
+        grid,
+        "stitching should undo splitting"
+    );
+
+    for &a in &Transform::ALL {
+        assert_eq!(a.then(a.inverse()), Transform::IDENTITY);
+        for &b in &Transform::ALL {
+            let pos = Pos { x: 3, y: 7 };
+            assert_eq!(a.then(b).apply(pos), b.apply(a.apply(pos)));
+        }
+    }
+
+    let sparse = grid.to_sparse(0).transform(Transform::ROTATE_LEFT);
+    assert_eq!(sparse[Pos { x: 1, y: -2 }], 12);
+    assert_eq!(sparse.border(Dir::North), [2, 12]);
+    assert_eq!(
+        sparse.sub_grid(Pos::origin(), Pos { x: 5, y: 5 }).bounds(),
+        Some((Pos::origin(), Pos { x: 1, y: 0 }))
+    );
+
+    // the example of 2017 day 21
+    let parse = |s: &str| DenseGrid::parse(&s.replace('/', "\n"), |_| false, |b| b == b'#').0;
+    let mut rules = HashMap::new();
+    for (from, to) in &[
+        ("../.#", "##./#../..."),
+        (".#./..#/###", "#..#/..../..../#..#"),
+    ] {
+        for from in parse(from).orientations() {
+            rules.insert(from, parse(to));
+        }
+    }
+    let mut grid = parse(".#./..#/###");
+    for _ in 0..2 {
+        let size = if grid.width() % 2 == 0 { 2 } else { 3 };
+        grid = DenseGrid::stitch(&grid.split(size, size).map(|tile| rules[&tile].clone()));
+    }
+    assert_eq!(grid.iter().filter(|&(_, &on)| on).count(), 12);
+}
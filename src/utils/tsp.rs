@@ -0,0 +1,141 @@
+use super::mask::Mask;
+use num::traits::Zero;
+
+/// The routes through every point that `shortest_route` and `longest_route` consider.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Route {
+    /// Paths that start and end anywhere.
+    Open,
+    /// Paths that start at the given point and end anywhere.
+    OpenFrom(usize),
+    /// Paths that start at point 0 and return to it at the end.
+    Closed,
+}
+
+/// The cheapest route through the points `0..n`, as the order in which they're visited and
+/// the total distance. The order of a closed route doesn't repeat point 0 at the end.
+/// `distance` doesn't need to be symmetric. Takes `O(2^n * n^2)` time.
+pub fn shortest_route<C>(
+    n: usize,
+    route: Route,
+    distance: impl Fn(usize, usize) -> C,
+) -> (Vec<usize>, C)
+where
+    C: Copy + Ord + Zero,
+{
+    held_karp(n, route, distance, |new, old| new < old)
+}
+
+/// Like `shortest_route`, but for the most expensive route.
+pub fn longest_route<C>(
+    n: usize,
+    route: Route,
+    distance: impl Fn(usize, usize) -> C,
+) -> (Vec<usize>, C)
+where
+    C: Copy + Ord + Zero,
+{
+    held_karp(n, route, distance, |new, old| new > old)
+}
+
+fn held_karp<C>(
+    n: usize,
+    route: Route,
+    distance: impl Fn(usize, usize) -> C,
+    better: impl Fn(C, C) -> bool,
+) -> (Vec<usize>, C)
+where
+    C: Copy + Zero,
+{
+    assert!(n < 32, "too many points: {}", n);
+    if n == 0 {
+        return (Vec::new(), C::zero());
+    }
+
+    let single = |i: u32| Mask(1 << i);
+    let all = Mask((1 << n) - 1);
+    let starts = match route {
+        Route::Open => all,
+        Route::OpenFrom(start) => {
+            assert!(start < n, "no point {} among {} points", start, n);
+            single(start as u32)
+        }
+        Route::Closed => single(0),
+    };
+
+    // the best route that visits `mask` and ends at `last`, and the point before `last`
+    let index = |mask: Mask<u32>, last: u32| mask.0 as usize * n + last as usize;
+    let mut best: Vec<Option<C>> = vec![None; n << n];
+    let mut previous = vec![0u8; n << n];
+    for start in starts.iter() {
+        best[index(single(start), start)] = Some(C::zero());
+    }
+
+    // every mask comes after its subsets
+    for mask in (1..=all.0).map(Mask) {
+        for last in mask.iter() {
+            let cost = match best[index(mask, last)] {
+                Some(cost) => cost,
+                None => continue,
+            };
+            for next in (all ^ mask).iter() {
+                let i = index(mask | single(next), next);
+                let cost = cost + distance(last as usize, next as usize);
+                if best[i].map_or(true, |old| better(cost, old)) {
+                    best[i] = Some(cost);
+                    previous[i] = last as u8;
+                }
+            }
+        }
+    }
+
+    let mut result: Option<(u32, C)> = None;
+    for last in all.iter() {
+        if let Some(mut cost) = best[index(all, last)] {
+            if route == Route::Closed {
+                cost = cost + distance(last as usize, 0);
+            }
+            if result.map_or(true, |(_, old)| better(cost, old)) {
+                result = Some((last, cost));
+            }
+        }
+    }
+
+    let (mut last, cost) = result.unwrap();
+    let mut mask = all;
+    let mut order = vec![last as usize];
+    while mask.len() > 1 {
+        let before = previous[index(mask, last)] as u32;
+        mask.remove(last);
+        last = before;
+        order.push(last as usize);
+    }
+    order.reverse();
+    (order, cost)
+}
+
+#[test]
+fn test_held_karp() {
+    // the example of 2015 day 9: London, Dublin, Belfast
+    let distances = [[0, 464, 518], [464, 0, 141], [518, 141, 0]];
+    let distance = |i: usize, j: usize| distances[i][j];
+    assert_eq!(shortest_route(3, Route::Open, distance).1, 605);
+    assert_eq!(longest_route(3, Route::Open, distance).1, 982);
+    assert_eq!(
+        shortest_route(3, Route::OpenFrom(0), distance),
+        (vec![0, 1, 2], 605)
+    );
+    assert_eq!(shortest_route(3, Route::Closed, distance).1, 1123);
+
+    // a directed ring, which is only cheap to follow forwards
+    let distance = |i: usize, j: usize| if j == (i + 1) % 6 { 1 } else { 10 };
+    assert_eq!(
+        shortest_route(6, Route::Closed, distance),
+        (vec![0, 1, 2, 3, 4, 5], 6)
+    );
+    assert_eq!(shortest_route(6, Route::OpenFrom(3), distance).1, 5);
+    assert_eq!(longest_route(6, Route::Open, distance).1, 50);
+
+    assert_eq!(shortest_route(1, Route::Closed, |_, _| 0), (vec![0], 0));
+    assert_eq!(shortest_route(0, Route::Open, |_, _| 0), (vec![], 0));
+}